@@ -0,0 +1,243 @@
+// src/calls.rs
+
+//! Calls from the token to other contracts.
+//!
+//! Every external call made by [`Erc20Aton`] goes through this module, so
+//! the contract code has a single path for both builds. The motsu test
+//! environment cannot execute other contracts, so test builds answer these
+//! calls with the programmable mocks in `src/test` instead.
+
+#[cfg(not(test))]
+pub(crate) use live::*;
+#[cfg(test)]
+pub(crate) use mock::*;
+
+#[cfg(not(test))]
+mod live {
+    use stylus_sdk::{
+        abi::Bytes,
+        alloy_primitives::{address, Address, FixedBytes, B256, I256, U256},
+        alloy_sol_types::SolValue,
+        call::{self, Call},
+    };
+
+    use crate::{Erc20Aton, IERC1363Receiver, IERC1363Spender, IERC3156FlashBorrower, IPriceFeed, IVault};
+
+    /// Address of the `ecrecover` precompile.
+    const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+
+    /// Calls `IVault::playerCommission` on `vault`.
+    pub(crate) fn player_commission(
+        storage: &mut Erc20Aton,
+        vault: Address,
+        player: Address,
+    ) -> Result<U256, Vec<u8>> {
+        Ok(IVault::new(vault).player_commission(Call::new_in(storage), player)?)
+    }
+
+    /// Calls `IVault::clearCommission` on `vault`.
+    pub(crate) fn clear_commission(
+        storage: &mut Erc20Aton,
+        vault: Address,
+        player: Address,
+    ) -> Result<(), Vec<u8>> {
+        Ok(IVault::new(vault).clear_commission(Call::new_in(storage), player)?)
+    }
+
+    /// Reads the latest answer of `feed`, with its update time and decimals.
+    pub(crate) fn latest_price(
+        storage: &Erc20Aton,
+        feed: Address,
+    ) -> Result<(I256, U256, u8), Vec<u8>> {
+        let oracle = IPriceFeed::new(feed);
+        let (_, answer, _, updated_at, _) = oracle.latest_round_data(storage)?;
+        Ok((answer, updated_at, oracle.decimals(storage)?))
+    }
+
+    /// Recovers the signer of `digest` through the `ecrecover` precompile.
+    /// Fails with empty revert data if no signer can be recovered.
+    pub(crate) fn ecrecover(
+        storage: &Erc20Aton,
+        digest: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<Address, Vec<u8>> {
+        let calldata = (digest, U256::from(v), r, s).abi_encode();
+        let output = call::static_call(storage, ECRECOVER, &calldata)?;
+        if output.len() != 32 {
+            return Err(Vec::new());
+        }
+        Ok(Address::from_slice(&output[12..]))
+    }
+
+    /// Calls `IERC1363Receiver::onTransferReceived` on `receiver`.
+    pub(crate) fn on_transfer_received(
+        storage: &mut Erc20Aton,
+        receiver: Address,
+        operator: Address,
+        from: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, Vec<u8>> {
+        Ok(IERC1363Receiver::new(receiver).on_transfer_received(
+            Call::new_in(storage),
+            operator,
+            from,
+            value,
+            data.0.into(),
+        )?)
+    }
+
+    /// Calls `IERC1363Spender::onApprovalReceived` on `spender`.
+    pub(crate) fn on_approval_received(
+        storage: &mut Erc20Aton,
+        spender: Address,
+        owner: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, Vec<u8>> {
+        Ok(IERC1363Spender::new(spender).on_approval_received(
+            Call::new_in(storage),
+            owner,
+            value,
+            data.0.into(),
+        )?)
+    }
+
+    /// Calls `IERC3156FlashBorrower::onFlashLoan` on `receiver`.
+    pub(crate) fn on_flash_loan(
+        storage: &mut Erc20Aton,
+        receiver: Address,
+        initiator: Address,
+        token: Address,
+        amount: U256,
+        fee: U256,
+        data: Bytes,
+    ) -> Result<B256, Vec<u8>> {
+        Ok(IERC3156FlashBorrower::new(receiver).on_flash_loan(
+            Call::new_in(storage),
+            initiator,
+            token,
+            amount,
+            fee,
+            data.0.into(),
+        )?)
+    }
+
+    /// Sends `amount` wei to `to`.
+    pub(crate) fn transfer_eth(
+        storage: &mut Erc20Aton,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Vec<u8>> {
+        call::transfer_eth(storage, to, amount)
+    }
+}
+
+#[cfg(test)]
+mod mock {
+    use ethers::types::{RecoveryMessage, Signature, H256};
+    use stylus_sdk::{
+        abi::Bytes,
+        alloy_primitives::{Address, FixedBytes, B256, I256, U256},
+    };
+
+    use crate::test::{mock_price_feed, mock_receiver, mock_vault, shims};
+    use crate::Erc20Aton;
+
+    /// Answers `IVault::playerCommission` from the mock vault.
+    pub(crate) fn player_commission(
+        _storage: &mut Erc20Aton,
+        vault: Address,
+        player: Address,
+    ) -> Result<U256, Vec<u8>> {
+        mock_vault::player_commission(vault, player)
+    }
+
+    /// Answers `IVault::clearCommission` from the mock vault.
+    pub(crate) fn clear_commission(
+        _storage: &mut Erc20Aton,
+        vault: Address,
+        player: Address,
+    ) -> Result<(), Vec<u8>> {
+        mock_vault::clear_commission(vault, player)
+    }
+
+    /// Answers the price feed reads from the mock feed.
+    pub(crate) fn latest_price(
+        _storage: &Erc20Aton,
+        feed: Address,
+    ) -> Result<(I256, U256, u8), Vec<u8>> {
+        mock_price_feed::latest_price(feed)
+    }
+
+    /// Recovers the signer of `digest` with `ethers`, accepting the same
+    /// `v` values as the `ecrecover` precompile.
+    pub(crate) fn ecrecover(
+        _storage: &Erc20Aton,
+        digest: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<Address, Vec<u8>> {
+        if v != 27 && v != 28 {
+            return Err(Vec::new());
+        }
+        let signature = Signature {
+            r: r.0.into(),
+            s: s.0.into(),
+            v: v.into(),
+        };
+        let signer = signature
+            .recover(RecoveryMessage::Hash(H256(digest.0)))
+            .map_err(|_| Vec::new())?;
+        Ok(Address::from(signer.0))
+    }
+
+    /// Answers `IERC1363Receiver::onTransferReceived` from the mock receiver.
+    pub(crate) fn on_transfer_received(
+        _storage: &mut Erc20Aton,
+        receiver: Address,
+        operator: Address,
+        from: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, Vec<u8>> {
+        mock_receiver::on_transfer_received(receiver, operator, from, value, data)
+    }
+
+    /// Answers `IERC1363Spender::onApprovalReceived` from the mock receiver.
+    pub(crate) fn on_approval_received(
+        _storage: &mut Erc20Aton,
+        spender: Address,
+        owner: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<FixedBytes<4>, Vec<u8>> {
+        mock_receiver::on_approval_received(spender, owner, value, data)
+    }
+
+    /// Answers `IERC3156FlashBorrower::onFlashLoan` from the mock receiver,
+    /// which may call back into the token.
+    pub(crate) fn on_flash_loan(
+        storage: &mut Erc20Aton,
+        receiver: Address,
+        initiator: Address,
+        token: Address,
+        amount: U256,
+        fee: U256,
+        data: Bytes,
+    ) -> Result<B256, Vec<u8>> {
+        mock_receiver::on_flash_loan(storage, receiver, initiator, token, amount, fee, data)
+    }
+
+    /// Moves ETH between the balances tracked by the shims.
+    pub(crate) fn transfer_eth(
+        _storage: &mut Erc20Aton,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Vec<u8>> {
+        shims::transfer_eth(to, amount)
+    }
+}
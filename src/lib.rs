@@ -1,21 +1,172 @@
 // Allow `cargo stylus export-abi` to generate a main function.
 #![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+// The exported router ABI nests one iterator adapter per entrypoint.
+#![recursion_limit = "256"]
 
 extern crate alloc;
+mod calls;
 mod test;
 
 // Imports from the Stylus SDK
 use stylus_sdk::prelude::*;
 use stylus_sdk::{
-    alloy_primitives::{Address, U256},
-    alloy_sol_types::sol,
-    call::transfer_eth,
-    call::Call,
-    contract, evm, msg,
+    abi::{router_entrypoint, Bytes, Router},
+    alloy_primitives::{aliases::{U64, U8}, uint, Address, FixedBytes, B256, I256, U256},
+    alloy_sol_types::{sol, SolValue},
+    block,
+    contract,
+    crypto::keccak,
+    evm,
+    keccak_const::Keccak256,
+    msg,
+    storage::{StorageVec, TopLevelStorage},
+    ArbResult,
 };
+use std::cell::Cell;
+
+/// Upper bound for the `s` value of a non-malleable ECDSA signature
+/// (secp256k1 curve order divided by two).
+const SECP256K1_HALF_ORDER: U256 =
+    uint!(0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF5D576E7357A4501DDFE92F46681B20A0_U256);
+
+/// Type string hashed into the EIP-712 domain typehash.
+const EIP712_DOMAIN_TYPEHASH: &[u8] =
+    b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+/// Type string hashed into the EIP-2612 permit typehash.
+const PERMIT_TYPEHASH: &[u8] =
+    b"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)";
+
+/// Type string hashed into the ERC-3009 `transferWithAuthorization` typehash.
+const TRANSFER_WITH_AUTHORIZATION_TYPEHASH: &[u8] = b"TransferWithAuthorization(address from,address to,uint256 value,uint256 validAfter,uint256 validBefore,bytes32 nonce)";
+
+/// Type string hashed into the ERC-3009 `receiveWithAuthorization` typehash.
+const RECEIVE_WITH_AUTHORIZATION_TYPEHASH: &[u8] = b"ReceiveWithAuthorization(address from,address to,uint256 value,uint256 validAfter,uint256 validBefore,bytes32 nonce)";
+
+/// Type string hashed into the ERC-3009 `cancelAuthorization` typehash.
+const CANCEL_AUTHORIZATION_TYPEHASH: &[u8] =
+    b"CancelAuthorization(address authorizer,bytes32 nonce)";
+
+/// Type string hashed into the ERC20Votes delegation typehash.
+const DELEGATION_TYPEHASH: &[u8] = b"Delegation(address delegatee,uint256 nonce,uint256 expiry)";
+
+/// ERC-6372 description of the clock used by voting checkpoints.
+const CLOCK_MODE: &str = "mode=blocknumber&from=default";
+
+/// Version of the EIP-712 signing domain.
+const EIP712_VERSION: &str = "1";
+
+/// Role that administers every other role by default.
+const DEFAULT_ADMIN_ROLE: B256 = B256::ZERO;
+
+/// `keccak256("ENGINE_ROLE")`: Arenaton stake engines.
+const ENGINE_ROLE: B256 = const_keccak(b"ENGINE_ROLE");
+
+/// `keccak256("PAUSER_ROLE")`: accounts allowed to pause the token.
+const PAUSER_ROLE: B256 = const_keccak(b"PAUSER_ROLE");
+
+/// `keccak256("VAULT_MANAGER_ROLE")`: accounts allowed to configure the vault.
+const VAULT_MANAGER_ROLE: B256 = const_keccak(b"VAULT_MANAGER_ROLE");
+
+/// `keccak256("MINTER_ROLE")`: accounts allowed to manage engine mint quotas.
+const MINTER_ROLE: B256 = const_keccak(b"MINTER_ROLE");
+
+/// Pause flag blocking every balance change made through `_update`.
+const PAUSE_TRANSFERS: u8 = 1 << 0;
+
+/// Pause flag blocking `mint_aton`.
+const PAUSE_MINT: u8 = 1 << 1;
+
+/// Pause flag blocking `swap`.
+const PAUSE_SWAP: u8 = 1 << 2;
+
+/// Every pause flag the token knows.
+const PAUSE_ALL: u8 = PAUSE_TRANSFERS | PAUSE_MINT | PAUSE_SWAP;
+
+/// `keccak256("ERC3156FlashBorrower.onFlashLoan")`: value flash loan
+/// receivers must return from `onFlashLoan`.
+const FLASH_LOAN_CALLBACK_SUCCESS: B256 = const_keccak(b"ERC3156FlashBorrower.onFlashLoan");
+
+/// `onTransferReceived(address,address,uint256,bytes)` selector that ERC-1363
+/// receivers must return.
+const ON_TRANSFER_RECEIVED: FixedBytes<4> =
+    const_selector(b"onTransferReceived(address,address,uint256,bytes)");
+
+/// `onApprovalReceived(address,uint256,bytes)` selector that ERC-1363
+/// spenders must return.
+const ON_APPROVAL_RECEIVED: FixedBytes<4> =
+    const_selector(b"onApprovalReceived(address,uint256,bytes)");
+
+/// Denominator of the flash loan fee, expressed in basis points.
+const FLASH_FEE_DENOMINATOR: u64 = 10_000;
+
+/// Computes the `keccak256` hash of `bytes` at compile time.
+const fn const_keccak(bytes: &[u8]) -> B256 {
+    B256::new(Keccak256::new().update(bytes).finalize())
+}
+
+/// Computes the 4-byte selector of a function signature at compile time.
+const fn const_selector(signature: &[u8]) -> FixedBytes<4> {
+    let hash = Keccak256::new().update(signature).finalize();
+    FixedBytes([hash[0], hash[1], hash[2], hash[3]])
+}
+
+/// Fixed-point precision of the ETH to ATON exchange rate: a rate of
+/// `RATE_PRECISION` mints one ATON per ETH.
+const RATE_PRECISION: U256 = uint!(1_000_000_000_000_000_000_U256);
+
+/// Status of a queued swap ticket awaiting liquidity.
+const TICKET_PENDING: u8 = 1;
+
+/// Status of a queued swap ticket that was paid out.
+const TICKET_FULFILLED: u8 = 2;
+
+/// Status of a queued swap ticket whose ATON was refunded.
+const TICKET_CANCELED: u8 = 3;
+
+/// Fixed-point precision of the accumulated commission per token.
+const COMMISSION_PRECISION: U256 = uint!(1_000_000_000_000_000_000_U256);
 
 // Storage definition for the ERC20Aton contract.
 sol_storage! {
+    /// Voting power of an account, or the total supply, as of `from_block`.
+    pub struct Checkpoint {
+        /// Block number from which `votes` applies.
+        uint64 from_block;
+        /// Votes held from `from_block` until the next checkpoint.
+        uint256 votes;
+    }
+
+    /// Request to redeem escrowed ATON for ETH once liquidity allows.
+    pub struct SwapTicket {
+        /// Account that escrowed the ATON and receives the ETH.
+        address owner;
+        /// ATON escrowed by the ticket.
+        uint256 amount;
+        /// One of the `TICKET_*` statuses.
+        uint8 status;
+    }
+
+    /// Part of a player's balance held by a stake engine for a bet.
+    pub struct BetLock {
+        /// Engine that created the lock and may release or settle it.
+        address engine;
+        /// Player whose balance is locked.
+        address player;
+        /// Amount of ATON locked.
+        uint256 amount;
+        /// Whether the lock is still in force.
+        bool active;
+    }
+
+    /// Balance of an account, or the total supply, as of snapshot `id`.
+    pub struct BalanceSnapshot {
+        /// First snapshot that `value` belongs to.
+        uint256 id;
+        /// Value recorded before the first change after snapshot `id`.
+        uint256 value;
+    }
+
     /// ERC20Aton storage structure containing core elements for an ERC20 token.
     ///
     /// # Fields
@@ -23,9 +174,48 @@ sol_storage! {
     /// - `balances`: Maps each address to its token balance.
     /// - `allowances`: Maps each owner to a mapping of spender addresses and their approved spending amounts.
     /// - `total_supply`: The total supply of tokens in existence.
-    /// - `stake_engine`: Mapping that indicates which addresses are allowed to mint tokens.
     /// - `vault_address`: Address of the vault contract for commission handling.
-    #[entrypoint]
+    /// - `nonces`: Maps each owner to the next EIP-2612 permit nonce.
+    /// - `mint_quota`: Remaining amount each stake engine is allowed to mint.
+    /// - `acc_commission_per_token`: Commission accumulated per eligible token, scaled by `COMMISSION_PRECISION`.
+    /// - `total_commission`: Total commission ever accumulated into the token.
+    /// - `commission_debt`: Accumulator checkpoint of each account at its last balance change.
+    /// - `commission_credit`: Commission settled to each account but not yet claimed.
+    /// - `strict_settlement`: Whether vault commission settlement fails closed.
+    /// - `pending_owner`: Address nominated to accept ownership in a two-step transfer.
+    /// - `initialized`: Whether `initialize` has run, even if ownership was renounced since.
+    /// - `roles`: Maps each role to the accounts holding it. Stake engines hold `ENGINE_ROLE`.
+    /// - `role_admins`: Maps each role to the role allowed to grant and revoke it.
+    /// - `paused`: Bitmask of the `PAUSE_*` flags currently in force.
+    /// - `entered`: Reentrancy guard flag, set while a guarded call runs.
+    /// - `flash_loan_active`: Set while a flash loan holds the reentrancy guard.
+    /// - `flash_fee_bps`: Flash loan fee in basis points, paid to the vault.
+    /// - `authorization_states`: Maps each authorizer to the ERC-3009 nonces already used or canceled.
+    /// - `delegates`: Maps each account to the delegate receiving its voting power.
+    /// - `vote_checkpoints`: History of each delegate's voting power, keyed by block number.
+    /// - `supply_checkpoints`: History of the total supply, keyed by block number.
+    /// - `current_snapshot_id`: Id of the most recent snapshot, zero before the first one.
+    /// - `account_snapshots`: Balances of each account, written lazily before they change after a snapshot.
+    /// - `supply_snapshots`: Total supply, written lazily before it changes after a snapshot.
+    /// - `name`, `symbol`, `decimals`: Token metadata, set once by `initialize`.
+    /// - `pending_vault`: Vault scheduled to replace `vault_address`, zero if none.
+    /// - `vault_update_eta`: Time from which `pending_vault` can be applied.
+    /// - `vault_delay`: Delay in seconds between scheduling and applying a vault update.
+    /// - `pending_vault_delay`: Lower delay scheduled to replace `vault_delay`.
+    /// - `vault_delay_eta`: Time from which `pending_vault_delay` can be applied, zero if none.
+    /// - `exchange_rate`: ATON minted per ETH, scaled by `RATE_PRECISION`; zero means one-to-one.
+    /// - `price_feed`: Optional aggregator overriding `exchange_rate`.
+    /// - `max_price_age`: Maximum age in seconds of a `price_feed` answer.
+    /// - `eth_deposited`: Total ETH ever deposited through `mint_aton` and `deposit_liquidity`.
+    /// - `eth_paid_out`: Total ETH ever paid out through `swap`.
+    /// - `eth_swept`: Total excess ETH ever swept by the owner.
+    /// - `swap_tickets`: Queued swap tickets by id, starting at one.
+    /// - `swap_ticket_count`: Id of the last ticket issued.
+    /// - `swap_queue_head`: Id of the last ticket the queue has moved past.
+    /// - `queued_aton`: ATON escrowed by pending tickets.
+    /// - `bet_locks`: Bet locks by bet id. A bet id is never reused.
+    /// - `locked_balances`: Part of each balance held by active bet locks, never spendable.
+    /// - `trusted_forwarder`: ERC-2771 forwarder whose calls are attributed to the appended sender.
     pub struct Erc20Aton {
         /// The owner of the contract with privileged access.
         address owner;
@@ -36,15 +226,143 @@ sol_storage! {
         mapping(address => mapping(address => uint256)) allowances;
         /// Total supply of the token.
         uint256 total_supply;
-        /// Mapping to track staking engines (privileged accounts for minting).
-        mapping(address => bool) stake_engine;
         /// Address of the vault contract for commission handling.
         address vault_address;
+        /// Mapping of owners to their current EIP-2612 permit nonce.
+        mapping(address => uint256) nonces;
+        /// Mapping of stake engines to the amount they may still mint.
+        mapping(address => uint256) mint_quota;
+        /// Commission accumulated per eligible token, scaled by `COMMISSION_PRECISION`.
+        uint256 acc_commission_per_token;
+        /// Total commission ever accumulated into the token.
+        uint256 total_commission;
+        /// Mapping of accounts to their accumulator checkpoint.
+        mapping(address => uint256) commission_debt;
+        /// Mapping of accounts to settled but unclaimed commission.
+        mapping(address => uint256) commission_credit;
+        /// Whether vault failures revert transfers instead of being skipped.
+        bool strict_settlement;
+        /// Address nominated to accept ownership.
+        address pending_owner;
+        /// Whether the contract has been initialized.
+        bool initialized;
+        /// Mapping of roles to their members.
+        mapping(bytes32 => mapping(address => bool)) roles;
+        /// Mapping of roles to their admin role.
+        mapping(bytes32 => bytes32) role_admins;
+        /// Bitmask of the active pause flags.
+        uint8 paused;
+        /// Whether a guarded call is in progress.
+        bool entered;
+        /// Whether a flash loan holds the reentrancy guard.
+        bool flash_loan_active;
+        /// Flash loan fee in basis points.
+        uint256 flash_fee_bps;
+        /// Mapping of authorizers to their used or canceled ERC-3009 nonces.
+        mapping(address => mapping(bytes32 => bool)) authorization_states;
+        /// Mapping of accounts to their voting delegate.
+        mapping(address => address) delegates;
+        /// Mapping of delegates to their voting power checkpoints.
+        mapping(address => Checkpoint[]) vote_checkpoints;
+        /// Total supply checkpoints.
+        Checkpoint[] supply_checkpoints;
+        /// Id of the most recent snapshot.
+        uint256 current_snapshot_id;
+        /// Mapping of accounts to their balance snapshots.
+        mapping(address => BalanceSnapshot[]) account_snapshots;
+        /// Total supply snapshots.
+        BalanceSnapshot[] supply_snapshots;
+        /// Token name.
+        string name;
+        /// Token symbol.
+        string symbol;
+        /// Token decimals.
+        uint8 decimals;
+        /// Vault scheduled to replace the current one.
+        address pending_vault;
+        /// Time from which the pending vault can be applied.
+        uint256 vault_update_eta;
+        /// Delay applied to vault updates, in seconds.
+        uint256 vault_delay;
+        /// Lower delay scheduled to replace the current one.
+        uint256 pending_vault_delay;
+        /// Time from which the pending delay can be applied.
+        uint256 vault_delay_eta;
+        /// ATON minted per ETH, scaled by `RATE_PRECISION`.
+        uint256 exchange_rate;
+        /// Aggregator providing the exchange rate, if any.
+        address price_feed;
+        /// Maximum age of a price feed answer, in seconds.
+        uint256 max_price_age;
+        /// Total ETH deposited through mints and liquidity deposits.
+        uint256 eth_deposited;
+        /// Total ETH paid out through swaps.
+        uint256 eth_paid_out;
+        /// Total excess ETH swept by the owner.
+        uint256 eth_swept;
+        /// Mapping of ticket ids to queued swap tickets.
+        mapping(uint256 => SwapTicket) swap_tickets;
+        /// Id of the last ticket issued.
+        uint256 swap_ticket_count;
+        /// Id of the last ticket processed or skipped by the queue.
+        uint256 swap_queue_head;
+        /// ATON escrowed by pending tickets.
+        uint256 queued_aton;
+        /// Mapping of bet ids to their locks.
+        mapping(uint256 => BetLock) bet_locks;
+        /// Mapping of players to their locked balance.
+        mapping(address => uint256) locked_balances;
+        /// ERC-2771 trusted forwarder, or zero if none.
+        address trusted_forwarder;
     }
 }
 
+unsafe impl TopLevelStorage for Erc20Aton {}
+
+thread_local! {
+    /// Sender appended to the current call by the trusted forwarder, if any.
+    /// Each Stylus call runs in a fresh instance, so this never outlives it.
+    static RELAYED_SENDER: Cell<Option<Address>> = const { Cell::new(None) };
+}
+
+/// Contract entrypoint.
+///
+/// Calls from the trusted forwarder carry the original sender as a 20-byte
+/// suffix (ERC-2771). The router rejects calldata with trailing bytes, so
+/// the suffix is stripped here and kept for [`Erc20Aton::_msg_sender`].
+#[entrypoint]
+fn user_main(mut input: Vec<u8>) -> ArbResult {
+    let storage = unsafe { <Erc20Aton as StorageType>::new(U256::ZERO, 0) };
+    let mut relayed = None;
+    if storage.is_trusted_forwarder(msg::sender()) && input.len() >= 20 {
+        let suffix = input.len() - 20;
+        relayed = Some(Address::from_slice(&input[suffix..]));
+        input.truncate(suffix);
+    }
+    RELAYED_SENDER.set(relayed);
+    router_entrypoint::<Erc20Aton, Erc20Aton>(input)
+}
+
+#[cfg(feature = "export-abi")]
+pub fn print_abi(license: &str, pragma: &str) {
+    stylus_sdk::abi::export::print_abi::<Erc20Aton>(license, pragma);
+}
+
 // Interface definition for the vault contract.
 sol_interface! {
+    /// Chainlink-style aggregator quoting the ATON price of one ETH.
+    ///
+    /// # Methods
+    /// - `decimals()`: Decimals of the reported answer.
+    /// - `latestRoundData()`: Latest answer and the time it was updated.
+    interface IPriceFeed {
+        /// Returns the number of decimals of the answer.
+        function decimals() external view returns (uint8);
+
+        /// Returns the latest round of the feed.
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+    }
+
     /// Vault interface for managing player commissions.
     ///
     /// # Methods
@@ -57,22 +375,87 @@ sol_interface! {
         /// Clears the commission for a specific player.
         function clearCommission(address player) external;
     }
+
+    /// ERC-1363 receiver interface for `transferAndCall` and `transferFromAndCall`.
+    ///
+    /// # Methods
+    /// - `onTransferReceived(...)`: Must return its own selector to accept the tokens.
+    interface IERC1363Receiver {
+        /// Handles the receipt of `value` tokens transferred by `operator` from `from`.
+        function onTransferReceived(address operator, address from, uint256 value, bytes calldata data) external returns (bytes4);
+    }
+
+    /// ERC-1363 spender interface for `approveAndCall`.
+    ///
+    /// # Methods
+    /// - `onApprovalReceived(...)`: Must return its own selector to accept the allowance.
+    interface IERC1363Spender {
+        /// Handles the approval of `value` tokens by `owner`.
+        function onApprovalReceived(address owner, uint256 value, bytes calldata data) external returns (bytes4);
+    }
+
+    /// ERC-3156 flash loan receiver interface.
+    ///
+    /// # Methods
+    /// - `onFlashLoan(...)`: Receives a flash loan and must return `FLASH_LOAN_CALLBACK_SUCCESS`.
+    interface IERC3156FlashBorrower {
+        /// Receives a flash loan of `amount` tokens, to be repaid with `fee`.
+        function onFlashLoan(address initiator, address token, uint256 amount, uint256 fee, bytes calldata data) external returns (bytes32);
+    }
 }
 
 // Definition of events, errors, and associated data structures for the contract.
 sol! {
     // Events related to commissions.
     event CommissionAccumulate(uint256 indexed amount, uint256 indexed newAccPerToken, uint256 indexed totalCommission);
+    event CommissionClaimed(address indexed account, uint256 amount);
+    event SettlementModeUpdated(bool strict);
     event EngineUpdated(address indexed account, bool status);
+    event MintQuotaUpdated(address indexed engine, uint256 quota);
     error Zero(address account);
 
     // Access control events.
     event EngineRoleGranted(address indexed account, address indexed sender);
     event EngineRoleRevoked(address indexed account, address indexed sender);
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleAdminChanged(bytes32 indexed role, bytes32 indexed previousAdminRole, bytes32 indexed newAdminRole);
+
+    // Access control errors.
+    error AccessControlUnauthorizedAccount(address account, bytes32 neededRole);
+    error AccessControlBadConfirmation();
 
     // Ownership-related events.
     event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+    event OwnershipTransferStarted(address indexed previous_owner, address indexed new_owner);
+    event OwnershipTransferCanceled(address indexed owner, address indexed pending_owner);
     error UnauthorizedAccount(address account);
+    error InvalidInitialization();
+    error MintQuotaExceeded(address engine, uint256 remaining, uint256 needed);
+
+    // Swap and reentrancy errors.
+    error ZeroSwapAmount(address account);
+    error InsufficientSwapBalance(address account, uint256 balance, uint256 needed);
+    error InsufficientLiquidity(uint256 available, uint256 needed);
+    error EthTransferFailed(address to, uint256 amount);
+    error ReentrancyGuardReentrantCall();
+
+    // ERC-1363 errors.
+    error ERC1363InvalidReceiver(address receiver);
+    error ERC1363InvalidSpender(address spender);
+
+    // ERC-3156 flash loan events and errors.
+    event FlashFeeUpdated(uint256 feeBps);
+    error ERC3156UnsupportedToken(address token);
+    error ERC3156ExceededMaxLoan(uint256 maxLoan);
+    error ERC3156InvalidReceiver(address receiver);
+    error FlashFeeTooHigh(uint256 feeBps);
+
+    // Pausable events and errors.
+    event Paused(address account, uint8 flags);
+    event Unpaused(address account, uint8 flags);
+    error EnforcedPause(uint8 flag);
+    error InvalidPauseFlags(uint8 flags);
 
     // ERC20 events.
     event Transfer(address indexed from, address indexed to, uint256 value);
@@ -85,6 +468,87 @@ sol! {
     error ERC20InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
     error ERC20InvalidSpender(address spender);
     error ERC20InvalidApprover(address approver);
+    error ERC20FailedDecreaseAllowance(address spender, uint256 currentAllowance, uint256 requestedDecrease);
+
+    // EIP-2612 permit errors.
+    error ERC2612ExpiredSignature(uint256 deadline);
+    error ERC2612InvalidSigner(address signer, address owner);
+    error ECDSAInvalidSignatureS(bytes32 s);
+
+    // ERC-3009 authorization events and errors.
+    event AuthorizationUsed(address indexed authorizer, bytes32 indexed nonce);
+    event AuthorizationCanceled(address indexed authorizer, bytes32 indexed nonce);
+    error AuthorizationNotYetValid(uint256 validAfter);
+    error AuthorizationExpired(uint256 validBefore);
+    error AuthorizationAlreadyUsed(address authorizer, bytes32 nonce);
+    error InvalidAuthorizationSigner(address signer, address authorizer);
+    error InvalidAuthorizationCaller(address caller, address payee);
+
+    // ERC20Votes events and errors.
+    event DelegateChanged(address indexed delegator, address indexed fromDelegate, address indexed toDelegate);
+    event DelegateVotesChanged(address indexed delegate, uint256 previousVotes, uint256 newVotes);
+    error VotesExpiredSignature(uint256 expiry);
+    error InvalidAccountNonce(address account, uint256 currentNonce);
+    error ECDSAInvalidSignature();
+    error ERC5805FutureLookup(uint256 timepoint, uint64 clock);
+
+    // Snapshot events and errors.
+    event Snapshot(uint256 id);
+    error ERC20SnapshotInvalidId(uint256 id, uint256 currentId);
+
+    // Vault management events and errors.
+    event VaultUpdated(address indexed oldVault, address indexed newVault);
+    event VaultUpdateScheduled(address indexed newVault, uint256 eta);
+    event VaultUpdateCanceled(address indexed newVault);
+    event VaultDelayUpdated(uint256 delay);
+    event VaultDelayUpdateScheduled(uint256 delay, uint256 eta);
+    error VaultUpdateNotReady(uint256 eta);
+    error NoPendingVaultUpdate();
+
+    // Exchange rate events and errors.
+    event ExchangeRateUpdated(uint256 rate);
+    event PriceFeedUpdated(address indexed feed, uint256 maxAge);
+    error InvalidExchangeRate(uint256 rate);
+    error InvalidPrice(int256 answer);
+    error StalePrice(uint256 updatedAt, uint256 maxAge);
+    error PriceFeedCallFailed(address feed);
+    error InvalidMaxPriceAge(uint256 maxAge);
+    error QuoteOverflow(uint256 amount, uint256 rate);
+    error SlippageExceeded(uint256 amountOut, uint256 minAmountOut);
+
+    // Reserve events and errors.
+    event ReserveUpdated(uint256 totalDeposited, uint256 totalPaidOut, uint256 totalSwept, uint256 balance);
+    error InsufficientExcess(uint256 excess, uint256 needed);
+
+    // Redemption queue events and errors.
+    event SwapRequested(uint256 indexed id, address indexed owner, uint256 amount);
+    event SwapFulfilled(uint256 indexed id, address indexed owner, uint256 amount, uint256 payout);
+    event SwapCanceled(uint256 indexed id, address indexed owner, uint256 amount);
+    error SwapTicketNotPending(uint256 id, uint8 status);
+
+    // Bet escrow events and errors.
+    event BalanceLocked(uint256 indexed betId, address indexed engine, address indexed player, uint256 amount);
+    event LockReleased(uint256 indexed betId, address indexed player, uint256 amount);
+    event BetSettled(uint256 indexed betId, address indexed player, address indexed winner, uint256 payout);
+    error BetAlreadyLocked(uint256 betId);
+    error BetNotLocked(uint256 betId);
+    error InvalidLockAmount(uint256 amount);
+    error PayoutExceedsLock(uint256 payout, uint256 locked);
+
+    // Batch transfer and multicall events and errors.
+    event BatchPayout(address indexed engine, uint256 recipients, uint256 total);
+    error BatchLengthMismatch(uint256 recipients, uint256 amounts);
+    error MulticallFailed(uint256 index, bytes reason);
+
+    // Meta-transaction events.
+    event TrustedForwarderUpdated(address indexed previousForwarder, address indexed newForwarder);
+
+    // Vault settlement errors.
+    error VaultCallFailed(address vault, address account);
+    error VaultUnderfunded(address vault, uint256 balance, uint256 needed);
+
+    // Commission errors.
+    error CommissionOverflow(address account);
 }
 
 /// Enum representing the various errors that can occur in the contract.
@@ -94,6 +558,95 @@ pub enum Error {
     Zero(Zero),
     /// Thrown when a caller without authorization attempts an owner-only function.
     UnauthorizedAccount(UnauthorizedAccount),
+    /// Thrown when `initialize` is called on an initialized contract.
+    InvalidInitialization(InvalidInitialization),
+    /// Thrown when the caller is missing the role required by a function.
+    AccessControlUnauthorizedAccount(AccessControlUnauthorizedAccount),
+    /// Thrown when an account renounces a role on behalf of another account.
+    AccessControlBadConfirmation(AccessControlBadConfirmation),
+    /// Thrown when `swap` is called with a zero amount.
+    ZeroSwapAmount(ZeroSwapAmount),
+    /// Thrown when the caller holds less ATON than it wants to swap.
+    InsufficientSwapBalance(InsufficientSwapBalance),
+    /// Thrown when the contract holds less ETH than a swap pays out.
+    InsufficientLiquidity(InsufficientLiquidity),
+    /// Thrown when sending ETH fails.
+    EthTransferFailed(EthTransferFailed),
+    /// Thrown when a guarded function is re-entered.
+    ReentrantCall(ReentrancyGuardReentrantCall),
+    /// Thrown when an ERC-3009 authorization is submitted before `validAfter`.
+    AuthorizationNotYetValid(AuthorizationNotYetValid),
+    /// Thrown when an ERC-3009 authorization is submitted after `validBefore`.
+    AuthorizationExpired(AuthorizationExpired),
+    /// Thrown when an ERC-3009 nonce was already used or canceled.
+    AuthorizationAlreadyUsed(AuthorizationAlreadyUsed),
+    /// Thrown when an ERC-3009 authorization was not signed by the authorizer.
+    InvalidAuthorizationSigner(InvalidAuthorizationSigner),
+    /// Thrown when `receiveWithAuthorization` is not called by the payee.
+    InvalidAuthorizationCaller(InvalidAuthorizationCaller),
+    /// Thrown when a delegation signature is submitted after its expiry.
+    VotesExpiredSignature(VotesExpiredSignature),
+    /// Thrown when a delegation signature uses a stale or future nonce.
+    InvalidAccountNonce(InvalidAccountNonce),
+    /// Thrown when no signer can be recovered from a signature.
+    InvalidSignature(ECDSAInvalidSignature),
+    /// Thrown when past votes are queried for the current or a future block.
+    FutureLookup(ERC5805FutureLookup),
+    /// Thrown when a snapshot id is zero or has not been taken yet.
+    InvalidSnapshotId(ERC20SnapshotInvalidId),
+    /// Thrown when a vault update is applied before its delay has passed.
+    VaultUpdateNotReady(VaultUpdateNotReady),
+    /// Thrown when no vault update is pending.
+    NoPendingVaultUpdate(NoPendingVaultUpdate),
+    /// Thrown when the exchange rate is zero.
+    InvalidExchangeRate(InvalidExchangeRate),
+    /// Thrown when the price feed reports a non-positive answer.
+    InvalidPrice(InvalidPrice),
+    /// Thrown when the price feed answer was never updated or is older than
+    /// `max_price_age`.
+    StalePrice(StalePrice),
+    /// Thrown when the price feed cannot be read.
+    PriceFeedCallFailed(PriceFeedCallFailed),
+    /// Thrown when a price feed is set with a zero maximum answer age.
+    InvalidMaxPriceAge(InvalidMaxPriceAge),
+    /// Thrown when a mint or swap quote overflows.
+    QuoteOverflow(QuoteOverflow),
+    /// Thrown when a mint or swap yields less than the caller's minimum.
+    SlippageExceeded(SlippageExceeded),
+    /// Thrown when a sweep would dip into the ETH backing the supply.
+    InsufficientExcess(InsufficientExcess),
+    /// Thrown when a swap ticket does not exist or is no longer pending.
+    SwapTicketNotPending(SwapTicketNotPending),
+    /// Thrown when a bet id has already been used for a lock.
+    BetAlreadyLocked(BetAlreadyLocked),
+    /// Thrown when a bet has no active lock.
+    BetNotLocked(BetNotLocked),
+    /// Thrown when a lock of zero ATON is requested.
+    InvalidLockAmount(InvalidLockAmount),
+    /// Thrown when a settlement pays out more than the bet locked.
+    PayoutExceedsLock(PayoutExceedsLock),
+    /// Thrown when batch arrays have different lengths.
+    BatchLengthMismatch(BatchLengthMismatch),
+    /// Thrown when a call inside a multicall fails.
+    MulticallFailed(MulticallFailed),
+    /// Thrown when an ERC-1363 receiver rejects a transfer.
+    InvalidCallReceiver(ERC1363InvalidReceiver),
+    /// Thrown when an ERC-1363 spender rejects an approval.
+    InvalidCallSpender(ERC1363InvalidSpender),
+    /// Thrown when a flash loan is requested for a token other than ATON.
+    UnsupportedToken(ERC3156UnsupportedToken),
+    /// Thrown when a flash loan exceeds the maximum loan.
+    ExceededMaxLoan(ERC3156ExceededMaxLoan),
+    /// Thrown when a flash loan receiver rejects the loan.
+    InvalidFlashReceiver(ERC3156InvalidReceiver),
+    /// Thrown when the flash loan fee is set above 100%.
+    FlashFeeTooHigh(FlashFeeTooHigh),
+    /// Thrown when an operation is blocked by an active pause flag.
+    EnforcedPause(EnforcedPause),
+    /// Thrown when pause flags contain unknown bits.
+    InvalidPauseFlags(InvalidPauseFlags),
+    /// Thrown when a stake engine tries to mint beyond its quota.
+    MintQuotaExceeded(MintQuotaExceeded),
     /// Thrown when the sender's balance is insufficient to complete a transfer.
     InsufficientBalance(ERC20InsufficientBalance),
     /// Thrown when the `from` address in a transfer is invalid.
@@ -106,6 +659,20 @@ pub enum Error {
     InvalidSpender(ERC20InvalidSpender),
     /// Thrown when the approver address is invalid.
     InvalidApprover(ERC20InvalidApprover),
+    /// Thrown when an allowance is decreased below zero.
+    FailedDecreaseAllowance(ERC20FailedDecreaseAllowance),
+    /// Thrown when a permit is submitted after its deadline.
+    ExpiredSignature(ERC2612ExpiredSignature),
+    /// Thrown when a permit signature was not produced by the owner.
+    InvalidSigner(ERC2612InvalidSigner),
+    /// Thrown when a signature has a malleable `s` value.
+    InvalidSignatureS(ECDSAInvalidSignatureS),
+    /// Thrown in strict settlement mode when a vault call fails.
+    VaultCallFailed(VaultCallFailed),
+    /// Thrown in strict settlement mode when the vault cannot cover commissions.
+    VaultUnderfunded(VaultUnderfunded),
+    /// Thrown when commission accounting would overflow.
+    CommissionOverflow(CommissionOverflow),
 }
 #[public]
 impl Erc20Aton {
@@ -113,23 +680,25 @@ impl Erc20Aton {
     pub fn number(&self) -> U256 {
         U256::from(0)
     }
-    /// Immutable token name
+    /// Token name, set once by `initialize`
     pub fn name(&self) -> String {
-        "ATON Stylus".into()
+        self.name.get_string()
     }
 
+    /// Address of the vault paying player commissions, or `Address::ZERO`
+    /// if none is set
     pub fn vault_address(&self) -> Address {
         self.vault_address.get()
     }
 
-    /// Immutable token symbol
+    /// Token symbol, set once by `initialize`
     pub fn symbol(&self) -> String {
-        "ATON".into()
+        self.symbol.get_string()
     }
 
-    /// Immutable token decimals
+    /// Token decimals, set once by `initialize`
     pub fn decimals(&self) -> u8 {
-        18u8
+        self.decimals.get().to::<u8>()
     }
 
     /// Total supply of tokens
@@ -143,520 +712,3330 @@ impl Erc20Aton {
     }
 
     /// Transfers `value` tokens from `from` to `to`
-    /// (msg::sender() must be able to spend at least `value` tokens from `from`)
+    /// (the caller must be able to spend at least `value` tokens from `from`;
+    /// an allowance of `U256::MAX` is infinite and never decreases)
     pub fn transfer_from(
         &mut self,
         from: Address,
         to: Address,
         value: U256,
     ) -> Result<bool, Error> {
-        // Check msg::sender() allowance
-        let mut sender_allowances = self.allowances.setter(from);
-        let mut allowance = sender_allowances.setter(msg::sender());
-        let old_allowance = allowance.get();
-        if old_allowance < value {
-            return Err(Error::InsufficientAllowance(
-                ERC20InsufficientAllowance {
-                    spender: msg::sender(),
-                    allowance: old_allowance,
-                    needed: value,
-                },
-            ));
-        }
-
-        // Decreases allowance
-        allowance.set(old_allowance - value);
+        // Spend the caller's allowance
+        let spender = self._msg_sender();
+        self._spend_allowance(from, spender, value)?;
 
-        self._pay_commissions(to, from).map_err(|_| {
-            Error::InsufficientBalance(ERC20InsufficientBalance {
-                sender: from,
-                balance: self.balances.get(from),
-                needed: value,
-            })
-        })?;
+        self._pay_commissions(to, from)?;
         // Calls the internal transfer function
         self._transfer(from, to, value)?;
 
         Ok(true)
     }
     fn approve(&mut self, spender: Address, value: U256) -> Result<bool, Error> {
-        let owner = msg::sender();
+        let owner = self._msg_sender();
         self._approve(owner, spender, value, true)
     }
 
-    /// Returns the allowance of `spender` on `owner`'s tokens
-    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
-        self.allowances.getter(owner).get(spender)
-    }
-
-    fn owner(&self) -> Address {
-        self.owner.get()
+    /// Increases the caller's allowance for `spender` by `added_value`,
+    /// without the race of replacing it with [`Self::approve`]. The
+    /// allowance saturates at `U256::MAX`, which is infinite.
+    ///
+    /// # Errors
+    ///
+    /// If `spender` is `Address::ZERO`, then the error
+    /// [`Error::InvalidSpender`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits an [`Approval`] event with the new allowance.
+    pub fn increase_allowance(
+        &mut self,
+        spender: Address,
+        added_value: U256,
+    ) -> Result<bool, Error> {
+        let owner = self._msg_sender();
+        let current_allowance = self.allowance(owner, spender);
+        self._approve(owner, spender, current_allowance.saturating_add(added_value), true)
     }
 
-    fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Error> {
-        self._only_owner()?;
-
-        if new_owner.is_zero() {
-            return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
-                account: Address::ZERO,
+    /// Decreases the caller's allowance for `spender` by
+    /// `requested_decrease`, without the race of replacing it with
+    /// [`Self::approve`].
+    ///
+    /// # Errors
+    ///
+    /// * If the allowance is below `requested_decrease`, then the error
+    ///   [`Error::FailedDecreaseAllowance`] is returned.
+    /// * If `spender` is `Address::ZERO`, then the error
+    ///   [`Error::InvalidSpender`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits an [`Approval`] event with the new allowance.
+    pub fn decrease_allowance(
+        &mut self,
+        spender: Address,
+        requested_decrease: U256,
+    ) -> Result<bool, Error> {
+        let owner = self._msg_sender();
+        let current_allowance = self.allowance(owner, spender);
+        if current_allowance < requested_decrease {
+            return Err(Error::FailedDecreaseAllowance(ERC20FailedDecreaseAllowance {
+                spender,
+                currentAllowance: current_allowance,
+                requestedDecrease: requested_decrease,
             }));
         }
-
-        self._transfer_ownership(new_owner);
-
-        Ok(())
-    }
-    pub fn initialize(&mut self) -> bool {
-        if self.owner.get() != Address::ZERO {
-            // Access the value using .get()
-            return false;
-        }
-        self.owner.set(msg::sender());
-        true
+        self._approve(owner, spender, current_allowance - requested_decrease, true)
     }
 
-    pub fn set_vault(&mut self, vault_address: Address) -> bool {
-        if self.vault_address.get() != Address::ZERO {
-            // Access the value using .get()
-            return false;
-        }
-        self.vault_address.set(vault_address);
-        true
+    /// Transfers `value` tokens to `to` and then calls `onTransferReceived`
+    /// on it (ERC-1363).
+    ///
+    /// # Errors
+    ///
+    /// If `to` does not return the `onTransferReceived` selector, then the
+    /// error [`Error::InvalidCallReceiver`] is returned.
+    pub fn transfer_and_call(&mut self, to: Address, value: U256) -> Result<bool, Error> {
+        self.transfer_and_call_with_data(to, value, Bytes(Vec::new()))
     }
 
-
-    pub fn vault(&self) -> Address {
-        self.vault_address.get()
+    /// [`Self::transfer_and_call`] with `data` forwarded to the receiver.
+    #[selector(name = "transferAndCall")]
+    pub fn transfer_and_call_with_data(
+        &mut self,
+        to: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<bool, Error> {
+        let sender = self._msg_sender();
+        self.transfer(to, value)?;
+        self._check_on_transfer_received(sender, sender, to, value, data)?;
+        Ok(true)
     }
 
-    pub fn transfer(&mut self, to: Address, amount: U256) -> Result<bool, Error> {
-        let caller = msg::sender();
-
-        self._pay_commissions(to, caller).map_err(|_| {
-            Error::InsufficientBalance(ERC20InsufficientBalance {
-                sender: msg::sender(),
-                balance: self.balances.get(caller),
-                needed: amount,
-            })
-        })?;
-
-        // Perform the transfer
-        self._transfer(caller, to, amount) // 100
-            .map(|_| true)
-            .map_err(|_| {
-                Error::InsufficientBalance(ERC20InsufficientBalance {
-                    sender: msg::sender(),
-                    needed: amount,
-                    balance: self.balances.get(msg::sender()),
-                })
-            })
+    /// Transfers `value` tokens from `from` to `to` using the caller's
+    /// allowance and then calls `onTransferReceived` on `to` (ERC-1363).
+    ///
+    /// # Errors
+    ///
+    /// If `to` does not return the `onTransferReceived` selector, then the
+    /// error [`Error::InvalidCallReceiver`] is returned.
+    pub fn transfer_from_and_call(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<bool, Error> {
+        self.transfer_from_and_call_with_data(from, to, value, Bytes(Vec::new()))
     }
 
-    #[payable]
-    pub fn mint_aton(&mut self) ->  Result<bool, Error> {
-        // if self.stake_engine.get(msg::sender()) == false {
-        //     return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
-        //         account: msg::sender(),
-                
-        //     }));
-        // }
-
-        let _ = self._mint(msg::sender(), msg::value());
-
-        // Emitting the transfer event
-        evm::log(Transfer {
-            from: Address::ZERO,
-            to: msg::sender(),
-            value: msg::value(),
-        });
-
+    /// [`Self::transfer_from_and_call`] with `data` forwarded to the receiver.
+    #[selector(name = "transferFromAndCall")]
+    pub fn transfer_from_and_call_with_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<bool, Error> {
+        self.transfer_from(from, to, value)?;
+        self._check_on_transfer_received(self._msg_sender(), from, to, value, data)?;
         Ok(true)
     }
 
+    /// Sets `value` as the allowance of `spender` and then calls
+    /// `onApprovalReceived` on it (ERC-1363).
+    ///
+    /// # Errors
+    ///
+    /// If `spender` does not return the `onApprovalReceived` selector, then
+    /// the error [`Error::InvalidCallSpender`] is returned.
+    pub fn approve_and_call(&mut self, spender: Address, value: U256) -> Result<bool, Error> {
+        self.approve_and_call_with_data(spender, value, Bytes(Vec::new()))
+    }
 
-
-    pub fn swap(&mut self, amount: U256) -> Result<bool, Error> {
-        let sender = msg::sender();
-
-        let contract_balance = contract::balance();
-
-        if amount == U256::from(0)
-            || self.balances.get(sender) < amount
-            || contract_balance < amount
-        {
-            return Err(Error::Zero(Zero { account: sender })); // Add the error struct
-        }
-        let _ = self._burn(sender, amount);
-        let _ = transfer_eth(sender, amount);
-
+    /// [`Self::approve_and_call`] with `data` forwarded to the spender.
+    #[selector(name = "approveAndCall")]
+    pub fn approve_and_call_with_data(
+        &mut self,
+        spender: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<bool, Error> {
+        let owner = self._msg_sender();
+        self._approve(owner, spender, value, true)?;
+        self._check_on_approval_received(owner, spender, value, data)?;
         Ok(true)
     }
 
-    /// Allows the owner to update the status of `stake_engine` for a specific address.
-    pub fn update_stake_engine(&mut self, account: Address, status: bool) -> Result<(), Error> {
-        // Ensure only the owner can call this function
-        self._only_owner()?;
-
-        // Update the `stake_engine` mapping
-        let mut engine = self.stake_engine.setter(account);
-        engine.set(status);
-
-        // Emit an event (optional, but recommended for transparency)
-        evm::log(EngineUpdated { account, status });
+    /// Returns the allowance of `spender` on `owner`'s tokens
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.allowances.getter(owner).get(spender)
+    }
 
-        Ok(())
+    /// Returns the current EIP-2612 nonce of `owner`.
+    ///
+    /// Every successful [`Self::permit`] call consumes the nonce, so a
+    /// signature can never be replayed.
+    pub fn nonces(&self, owner: Address) -> U256 {
+        self.nonces.get(owner)
     }
 
-    pub fn is_stake_engine(&self, account: Address) -> bool {
-        self.stake_engine.get(account)
+    /// Returns the EIP-712 domain separator used to sign permits.
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> B256 {
+        self._domain_separator()
     }
-}
 
-// Private Functions
-impl Erc20Aton {
-    /// Sets a `value` number of tokens as the allowance of `spender` over the
-    /// caller's tokens.
-    ///
-    /// Returns a boolean value indicating whether the operation succeeded.
-    /// # Arguments
-    ///
-    /// * `&mut self` - Write access to the contract's state.
-    /// * `owner` - Account that owns the tokens.
-    /// * `spender` - Account that will spend the tokens.
-    /// * `emit_event` - Emit an [`Approval`] event flag.
+    /// Sets `value` as the allowance of `spender` over `owner`'s tokens,
+    /// given `owner`'s signed approval (EIP-2612).
     ///
     /// # Errors
     ///
-    /// If the `spender` address is `Address::ZERO`, then the error
-    /// [`Error::InvalidSpender`] is returned.
+    /// * If `deadline` is in the past, then the error
+    ///   [`Error::ExpiredSignature`] is returned.
+    /// * If the signature has a malleable `s` value, then the error
+    ///   [`Error::InvalidSignatureS`] is returned.
+    /// * If the recovered signer is not `owner`, then the error
+    ///   [`Error::InvalidSigner`] is returned.
     ///
     /// # Events
     ///
     /// Emits an [`Approval`] event.
-    fn _approve(
+    #[allow(clippy::too_many_arguments)]
+    pub fn permit(
         &mut self,
         owner: Address,
         spender: Address,
         value: U256,
-        emit_event: bool,
-    ) -> Result<bool, Error> {
-        if owner.is_zero() {
-            return Err(Error::InvalidApprover(ERC20InvalidApprover {
-                approver: Address::ZERO,
-            }));
+        deadline: U256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Error> {
+        if U256::from(block::timestamp()) > deadline {
+            return Err(Error::ExpiredSignature(ERC2612ExpiredSignature { deadline }));
         }
 
-        if spender.is_zero() {
-            return Err(Error::InvalidSpender(ERC20InvalidSpender {
-                spender: Address::ZERO,
-            }));
+        let nonce = self._use_nonce(owner);
+        let digest = self._permit_digest(owner, spender, value, nonce, deadline);
+        let signer = self._recover(digest, v, r, s)?;
+        if signer.is_zero() || signer != owner {
+            return Err(Error::InvalidSigner(ERC2612InvalidSigner { signer, owner }));
         }
 
-        self.allowances.setter(owner).insert(spender, value);
-        if emit_event {
-            evm::log(Approval {
-                owner,
-                spender,
-                value,
-            });
-        }
-        Ok(true)
+        self._approve(owner, spender, value, true)?;
+        Ok(())
     }
-    /// Internal implementation of transferring tokens between two accounts.
+
+    /// Returns whether the ERC-3009 `nonce` of `authorizer` was already used
+    /// or canceled.
+    pub fn authorization_state(&self, authorizer: Address, nonce: B256) -> bool {
+        self.authorization_states.getter(authorizer).get(nonce)
+    }
+
+    /// Transfers `value` tokens from `from` to `to`, given `from`'s signed
+    /// authorization (ERC-3009). Anyone may relay the authorization.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `&mut self` - Write access to the contract's state.
-    /// * `from` - Account to transfer tokens from.
-    /// * `to` - Account to transfer tokens to.
-    /// * `value` - The number of tokens to transfer.
+    /// * If the authorization is outside its validity window, then the error
+    ///   [`Error::AuthorizationNotYetValid`] or [`Error::AuthorizationExpired`]
+    ///   is returned.
+    /// * If `nonce` was already used or canceled, then the error
+    ///   [`Error::AuthorizationAlreadyUsed`] is returned.
+    /// * If the recovered signer is not `from`, then the error
+    ///   [`Error::InvalidAuthorizationSigner`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits an [`AuthorizationUsed`] and a [`Transfer`] event.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_with_authorization(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Error> {
+        let digest = self._authorization_digest(
+            TRANSFER_WITH_AUTHORIZATION_TYPEHASH,
+            from,
+            to,
+            value,
+            valid_after,
+            valid_before,
+            nonce,
+        );
+        self._use_authorization(from, nonce, valid_after, valid_before, digest, v, r, s)?;
+
+        self._pay_commissions(to, from)?;
+        self._transfer(from, to, value)
+    }
+
+    /// Transfers `value` tokens from `from` to the caller, given `from`'s
+    /// signed authorization (ERC-3009).
+    ///
+    /// Unlike [`Self::transfer_with_authorization`], only the payee can submit
+    /// the authorization, so it cannot be front-run out of a deposit flow.
     ///
     /// # Errors
     ///
-    /// * If the `from` address is `Address::ZERO`, then the error
-    ///   [`Error::InvalidSender`] is returned.
-    /// * If the `to` address is `Address::ZERO`, then the error
-    ///   [`Error::InvalidReceiver`] is returned.
-    /// * If the `from` address doesn't have enough tokens, then the error
-    ///   [`Error::InsufficientBalance`] is returned.
+    /// * If the caller is not `to`, then the error
+    ///   [`Error::InvalidAuthorizationCaller`] is returned.
+    /// * Otherwise, the same errors as [`Self::transfer_with_authorization`].
     ///
     /// # Events
     ///
-    /// Emits a [`Transfer`] event.
-    fn _transfer(&mut self, from: Address, to: Address, value: U256) -> Result<(), Error> {
-        if from.is_zero() {
-            return Err(Error::InvalidSender(ERC20InvalidSender {
-                sender: Address::ZERO,
+    /// Emits an [`AuthorizationUsed`] and a [`Transfer`] event.
+    #[allow(clippy::too_many_arguments)]
+    pub fn receive_with_authorization(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Error> {
+        let caller = self._msg_sender();
+        if caller != to {
+            return Err(Error::InvalidAuthorizationCaller(InvalidAuthorizationCaller {
+                caller,
+                payee: to,
             }));
         }
-        if to.is_zero() {
-            return Err(Error::InvalidReceiver(ERC20InvalidReceiver {
-                receiver: Address::ZERO,
-            }));
-        }
-
-        self._update(from, to, value)?;
 
-        Ok(())
+        let digest = self._authorization_digest(
+            RECEIVE_WITH_AUTHORIZATION_TYPEHASH,
+            from,
+            to,
+            value,
+            valid_after,
+            valid_before,
+            nonce,
+        );
+        self._use_authorization(from, nonce, valid_after, valid_before, digest, v, r, s)?;
+
+        self._pay_commissions(to, from)?;
+        self._transfer(from, to, value)
     }
 
-    /// Destroys a `value` amount of tokens from `account`,
-    /// lowering the total supply.
-    ///
-    /// Relies on the `update` mechanism.
-    ///
-    /// # Arguments
-    ///
-    /// * `account` - Owner's address.
-    /// * `value` - Amount to be burnt.
+    /// Cancels the unused ERC-3009 `nonce` of `authorizer`, given
+    /// `authorizer`'s signature.
     ///
     /// # Errors
     ///
-    /// * If the `from` address is `Address::ZERO`, then the error
-    ///   [`Error::InvalidSender`] is returned.
-    /// * If the `from` address doesn't have enough tokens, then the error
-    ///   [`Error::InsufficientBalance`] is returned.
+    /// * If `nonce` was already used or canceled, then the error
+    ///   [`Error::AuthorizationAlreadyUsed`] is returned.
+    /// * If the recovered signer is not `authorizer`, then the error
+    ///   [`Error::InvalidAuthorizationSigner`] is returned.
     ///
     /// # Events
     ///
-    /// Emits a [`Transfer`] event.
-    pub fn _burn(&mut self, account: Address, value: U256) -> Result<(), Error> {
-        if account == Address::ZERO {
-            return Err(Error::InvalidSender(ERC20InvalidSender {
-                sender: Address::ZERO,
-            }));
-        }
-        self._update(account, Address::ZERO, value)
+    /// Emits an [`AuthorizationCanceled`] event.
+    pub fn cancel_authorization(
+        &mut self,
+        authorizer: Address,
+        nonce: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Error> {
+        self._require_unused_authorization(authorizer, nonce)?;
+
+        let digest = self._cancel_authorization_digest(authorizer, nonce);
+        self._check_authorization_signer(authorizer, digest, v, r, s)?;
+
+        self.authorization_states.setter(authorizer).insert(nonce, true);
+        evm::log(AuthorizationCanceled { authorizer, nonce });
+
+        Ok(())
     }
 
-    /// Updates `owner`'s allowance for `spender` based on spent `value`.
-    ///
-    /// Does not update the allowance value in the case of infinite allowance.
+    /// Returns the current block number, the clock of voting checkpoints
+    /// (ERC-6372).
+    pub fn clock(&self) -> u64 {
+        block::number()
+    }
+
+    /// Describes the clock used by voting checkpoints (ERC-6372).
+    #[selector(name = "CLOCK_MODE")]
+    pub fn clock_mode(&self) -> String {
+        CLOCK_MODE.into()
+    }
+
+    /// Returns the delegate receiving the voting power of `account`.
+    pub fn delegates(&self, account: Address) -> Address {
+        self.delegates.get(account)
+    }
+
+    /// Returns the current voting power of `account`.
+    pub fn get_votes(&self, account: Address) -> U256 {
+        Self::_latest_checkpoint(&self.vote_checkpoints.get(account))
+    }
+
+    /// Returns the voting power of `account` at the end of block `timepoint`.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `&mut self` - Write access to the contract's state.
-    /// * `owner` - Account to transfer tokens from.
-    /// * `to` - Account to transfer tokens to.
-    /// * `value` - The number of tokens to transfer.
+    /// If `timepoint` is not in the past, then the error
+    /// [`Error::FutureLookup`] is returned.
+    pub fn get_past_votes(&self, account: Address, timepoint: U256) -> Result<U256, Error> {
+        let timepoint = self._past_timepoint(timepoint)?;
+        Ok(Self::_lookup_checkpoint(&self.vote_checkpoints.get(account), timepoint))
+    }
+
+    /// Returns the total supply at the end of block `timepoint`.
     ///
     /// # Errors
     ///
-    /// If not enough allowance is available, then the error
-    /// [`Error::InsufficientAllowance`] is returned.
+    /// If `timepoint` is not in the past, then the error
+    /// [`Error::FutureLookup`] is returned.
+    pub fn get_past_total_supply(&self, timepoint: U256) -> Result<U256, Error> {
+        let timepoint = self._past_timepoint(timepoint)?;
+        Ok(Self::_lookup_checkpoint(&self.supply_checkpoints, timepoint))
+    }
+
+    /// Delegates the caller's voting power to `delegatee`.
     ///
     /// # Events
     ///
-    /// Emits an [`Approval`] event.
-    pub fn _spend_allowance(
+    /// Emits a [`DelegateChanged`] event, and a [`DelegateVotesChanged`]
+    /// event for each delegate whose voting power changes.
+    pub fn delegate(&mut self, delegatee: Address) {
+        self._delegate(self._msg_sender(), delegatee);
+    }
+
+    /// Delegates the signer's voting power to `delegatee`, given a signed
+    /// EIP-712 delegation.
+    ///
+    /// The delegation uses the same sequential nonces as [`Self::permit`].
+    ///
+    /// # Errors
+    ///
+    /// * If `expiry` is in the past, then the error
+    ///   [`Error::VotesExpiredSignature`] is returned.
+    /// * If no signer can be recovered, then the error
+    ///   [`Error::InvalidSignature`] is returned.
+    /// * If `nonce` is not the signer's current nonce, then the error
+    ///   [`Error::InvalidAccountNonce`] is returned.
+    pub fn delegate_by_sig(
         &mut self,
-        owner: Address,
-        spender: Address,
-        value: U256,
+        delegatee: Address,
+        nonce: U256,
+        expiry: U256,
+        v: u8,
+        r: B256,
+        s: B256,
     ) -> Result<(), Error> {
-        let current_allowance = self.allowance(owner, spender);
-        if current_allowance != U256::MAX {
-            if current_allowance < value {
-                return Err(Error::InsufficientAllowance(
-                    ERC20InsufficientAllowance {
-                        spender,
-                        allowance: current_allowance,
-                        needed: value,
-                    },
-                ));
-            }
+        if U256::from(block::timestamp()) > expiry {
+            return Err(Error::VotesExpiredSignature(VotesExpiredSignature { expiry }));
+        }
 
-            self._approve(owner, spender, current_allowance - value, false)?;
+        let digest = self._delegation_digest(delegatee, nonce, expiry);
+        let signer = self._recover(digest, v, r, s)?;
+        if signer.is_zero() {
+            return Err(Error::InvalidSignature(ECDSAInvalidSignature {}));
         }
 
+        let current = self._use_nonce(signer);
+        if current != nonce {
+            return Err(Error::InvalidAccountNonce(InvalidAccountNonce {
+                account: signer,
+                currentNonce: current,
+            }));
+        }
+
+        self._delegate(signer, delegatee);
         Ok(())
     }
 
-    /// Creates a `value` amount of tokens and assigns them to `account`,
-    /// by transferring it from `Address::ZERO`.
-    ///
-    /// Relies on the `_update` mechanism.
-    ///
-    /// # Panics
-    ///
-    /// If `total_supply` exceeds `U256::MAX`.
+    /// Takes a snapshot of all balances and the total supply, and returns
+    /// its id. Only callable by stake engines and the owner.
     ///
     /// # Errors
     ///
-    /// If the `account` address is `Address::ZERO`, then the error
-    /// [`Error::InvalidReceiver`] is returned.
+    /// If the caller is neither the owner nor holds `ENGINE_ROLE`, then the
+    /// error [`Error::AccessControlUnauthorizedAccount`] is returned.
     ///
     /// # Events
     ///
-    /// Emits a [`Transfer`] event.
-    pub fn _mint(&mut self, account: Address, value: U256) -> Result<(), Error> {
-        if account.is_zero() {
-            return Err(Error::InvalidReceiver(ERC20InvalidReceiver {
-                receiver: Address::ZERO,
-            }));
+    /// Emits a [`Snapshot`] event.
+    pub fn snapshot(&mut self) -> Result<U256, Error> {
+        let caller = self._msg_sender();
+        if self.owner.get() != caller {
+            self._check_role(ENGINE_ROLE, caller)?;
         }
-        self._update(Address::ZERO, account, value)
+
+        let id = self.current_snapshot_id.get() + U256::from(1);
+        self.current_snapshot_id.set(id);
+        evm::log(Snapshot { id });
+
+        Ok(id)
     }
-    /// Transfers a `value` amount of tokens from `from` to `to`, or
-    /// alternatively mints (or burns) if `from` (or `to`) is the zero address.
-    ///
-    /// All customizations to transfers, mints, and burns should be done by
-    /// using this function.
-    ///
-    /// # Arguments
-    ///
-    /// * `from` - Owner's address.
-    /// * `to` - Recipient's address.
-    /// * `value` - Amount to be transferred.
-    ///
-    /// # Panics
-    ///
-    /// If `total_supply` exceeds `U256::MAX`. It may happen during `mint`
-    /// operation.
+
+    /// Returns the id of the most recent snapshot, or zero if none was taken.
+    pub fn current_snapshot_id(&self) -> U256 {
+        self.current_snapshot_id.get()
+    }
+
+    /// Returns the balance of `account` at snapshot `id`.
     ///
     /// # Errors
     ///
-    /// If the `from` address doesn't have enough tokens, then the error
-    /// [`Error::InsufficientBalance`] is returned.
+    /// If `id` is zero or greater than the current snapshot id, then the
+    /// error [`Error::InvalidSnapshotId`] is returned.
+    pub fn balance_of_at(&self, account: Address, id: U256) -> Result<U256, Error> {
+        let snapshotted = self._value_at(&self.account_snapshots.get(account), id)?;
+        Ok(snapshotted.unwrap_or_else(|| self.balances.get(account)))
+    }
+
+    /// Returns the total supply at snapshot `id`.
     ///
-    /// # Events
+    /// # Errors
     ///
-    /// Emits a [`Transfer`] event.
-    pub fn _update(&mut self, from: Address, to: Address, value: U256) -> Result<(), Error> {
-        if from.is_zero() {
-            // Mint operation. Overflow check required: the rest of the code
-            // assumes that `total_supply` never overflows.
-            let current_supply = self.total_supply.get();
-            let new_supply = current_supply.checked_add(value).ok_or_else(|| {
-                Error::InsufficientBalance(ERC20InsufficientBalance {
-                    sender: from,
-                    balance: current_supply,
-                    needed: value,
-                })
-            })?;
-            self.total_supply.set(new_supply);
-        } else {
-            // Check the `from` balance before deduction
-            let from_balance = self.balances.get(from);
-            if from_balance < value {
-                return Err(Error::InsufficientBalance(ERC20InsufficientBalance {
-                    sender: from,
-                    balance: from_balance,
-                    needed: value,
-                }));
-            }
-            // Safely decrease the `from` balance
-            self.balances.setter(from).set(from_balance - value);
-        }
+    /// If `id` is zero or greater than the current snapshot id, then the
+    /// error [`Error::InvalidSnapshotId`] is returned.
+    pub fn total_supply_at(&self, id: U256) -> Result<U256, Error> {
+        let snapshotted = self._value_at(&self.supply_snapshots, id)?;
+        Ok(snapshotted.unwrap_or_else(|| self.total_supply.get()))
+    }
 
-        if to.is_zero() {
-            // Burn operation: decrease total supply
-            let current_supply = self.total_supply.get();
-            let new_supply = current_supply.checked_sub(value).ok_or_else(|| {
-                Error::InsufficientBalance(ERC20InsufficientBalance {
-                    sender: from,
-                    balance: current_supply,
-                    needed: value,
-                })
-            })?;
-            self.total_supply.set(new_supply);
-        } else {
-            // Safely increase the `to` balance
-            let to_balance = self.balances.get(to);
-            let new_balance = to_balance.checked_add(value).ok_or_else(|| {
-                Error::InsufficientBalance(ERC20InsufficientBalance {
-                    sender: to,
-                    balance: to_balance,
-                    needed: value,
-                })
-            })?;
-            self.balances.setter(to).set(new_balance);
+    fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    /// Starts a two-step ownership transfer to `new_owner`, who must call
+    /// [`Self::accept_ownership`] to complete it. Only callable by the owner.
+    fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Error> {
+        self._only_owner()?;
+
+        if new_owner.is_zero() {
+            return Err(Error::Zero(Zero { account: new_owner }));
         }
 
-        // Emit a Transfer event
-        evm::log(Transfer { from, to, value });
+        self.pending_owner.set(new_owner);
+        evm::log(OwnershipTransferStarted {
+            previous_owner: self.owner.get(),
+            new_owner,
+        });
 
         Ok(())
     }
 
-    // Ownable
-    pub fn _only_owner(&self) -> Result<(), Error> {
-        let account = msg::sender();
-        if self.owner.get() != account {
+    /// Returns the address that may accept ownership, if any.
+    pub fn pending_owner(&self) -> Address {
+        self.pending_owner.get()
+    }
+
+    /// Completes a two-step ownership transfer. Only callable by the pending
+    /// owner.
+    pub fn accept_ownership(&mut self) -> Result<(), Error> {
+        let sender = self._msg_sender();
+        if self.pending_owner.get() != sender {
             return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
-                account,
+                account: sender,
             }));
         }
 
+        self._transfer_ownership(sender);
+
         Ok(())
     }
 
-    pub fn _transfer_ownership(&mut self, new_owner: Address) {
-        let previous_owner = self.owner.get();
-        self.owner.set(new_owner);
-        evm::log(OwnershipTransferred {
-            previous_owner,
-            new_owner,
-        });
+    /// Cancels a pending ownership transfer. Only callable by the owner.
+    ///
+    /// # Events
+    ///
+    /// Emits an [`OwnershipTransferCanceled`] event if a transfer was
+    /// pending.
+    pub fn cancel_ownership_transfer(&mut self) -> Result<(), Error> {
+        self._only_owner()?;
+
+        let pending_owner = self.pending_owner.get();
+        if !pending_owner.is_zero() {
+            self.pending_owner.set(Address::ZERO);
+            evm::log(OwnershipTransferCanceled {
+                owner: self.owner.get(),
+                pending_owner,
+            });
+        }
+
+        Ok(())
     }
 
-    // Helper function to calculate commission
-    fn _player_commission(&mut self, vault: &IVault, account: Address) -> U256 {
-        vault
-            .player_commission(Call::new_in(self), account)
-            .map_err(|_| Error::Zero(Zero { account }))
-            .unwrap_or_default()
+    /// Leaves the contract without an owner, disabling the owner-only
+    /// functions for good. Other holders of `DEFAULT_ADMIN_ROLE` keep
+    /// managing roles. Only callable by the owner.
+    pub fn renounce_ownership(&mut self) -> Result<(), Error> {
+        self._only_owner()?;
+
+        self._transfer_ownership(Address::ZERO);
+
+        Ok(())
     }
 
-    // Helper function to perform a transfer
-    pub fn _perform_transfer(
+    /// Initializes the token metadata, vault and stake engines in one call,
+    /// and designates the caller as the owner.
+    ///
+    /// `vault` may be `Address::ZERO` to run without commission settlement.
+    ///
+    /// # Errors
+    ///
+    /// * If the contract is already initialized, then the error
+    ///   [`Error::InvalidInitialization`] is returned.
+    /// * If any of `engines` is `Address::ZERO`, then the error
+    ///   [`Error::Zero`] is returned.
+    pub fn initialize(
         &mut self,
-        from: Address,
-        to: Address,
-        amount: U256,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        vault: Address,
+        engines: Vec<Address>,
     ) -> Result<(), Error> {
-        self._transfer(from, to, amount).map_err(|_| {
-            Error::InsufficientBalance(ERC20InsufficientBalance {
-                sender: from,
-                needed: amount,
-                balance: self.balances.get(from),
-            })
-        })
-    }
+        // A renounced contract has no owner but must stay initialized
+        if self.initialized.get() || self.owner.get() != Address::ZERO {
+            return Err(Error::InvalidInitialization(InvalidInitialization {}));
+        }
+        if let Some(engine) = engines.iter().find(|engine| engine.is_zero()) {
+            return Err(Error::Zero(Zero { account: *engine }));
+        }
 
-    // Helper function to clear commission in the vault
-    fn _clear_commission(&mut self, vault: &IVault, account: Address) -> Result<(), Error> {
-        vault
-            .clear_commission(Call::new_in(self), account)
-            .map_err(|_| Error::Zero(Zero { account }))
-    }
+        self.initialized.set(true);
+        let owner = self._msg_sender();
+        self.owner.set(owner);
+        self._grant_role(DEFAULT_ADMIN_ROLE, owner);
 
-    pub fn _pay_commissions(&mut self, to: Address, from: Address) -> Result<(), Error> {
-        let vault_contract = IVault::new(self.vault_address.get());
+        self.name.set_str(name);
+        self.symbol.set_str(symbol);
+        self.decimals.set(U8::from(decimals));
+        if !vault.is_zero() {
+            self._set_vault(vault);
+        }
+        for engine in engines {
+            self._grant_role(ENGINE_ROLE, engine);
+        }
+
+        Ok(())
+    }
 
-        let from_commission = self._player_commission(&vault_contract, from);
-        let to_commission = self._player_commission(&vault_contract, to);
+    /// Sets or rotates the vault to `new_vault`. Only callable by the owner.
+    ///
+    /// With a zero vault delay the change applies at once. Otherwise it is
+    /// scheduled and must be applied with [`Self::execute_vault_update`] once
+    /// the delay has passed, replacing any update already pending.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller is not the owner, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If `new_vault` is `Address::ZERO`, then the error [`Error::Zero`] is
+    ///   returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`VaultUpdated`] event, or a [`VaultUpdateScheduled`] event
+    /// when the delay is not zero.
+    pub fn set_vault(&mut self, new_vault: Address) -> Result<(), Error> {
+        self._only_owner()?;
+        if new_vault.is_zero() {
+            return Err(Error::Zero(Zero { account: new_vault }));
+        }
 
-        let _to = to;
-        let _from = from;
-        let _owner = self.owner.get();
-        let mut owner_commission = U256::from(0);
-        if from == contract::address() || to == contract::address() {
-            owner_commission = self._player_commission(&vault_contract, to);
+        let delay = self.vault_delay.get();
+        if delay.is_zero() {
+            // An update applied at once supersedes any pending one
+            let pending_vault = self.pending_vault.get();
+            if !pending_vault.is_zero() {
+                self._clear_pending_vault();
+                evm::log(VaultUpdateCanceled { newVault: pending_vault });
+            }
+            self._set_vault(new_vault);
+            return Ok(());
         }
-        let vault_address = self.vault_address.get();
 
-        let _ = self._perform_transfer(vault_address, _to, to_commission);
-        let _ = self._perform_transfer(vault_address, _from, from_commission);
+        let eta = U256::from(block::timestamp()).saturating_add(delay);
+        self.pending_vault.set(new_vault);
+        self.vault_update_eta.set(eta);
+        evm::log(VaultUpdateScheduled { newVault: new_vault, eta });
 
-        let _ = self._clear_commission(&vault_contract, to);
-        let _ = self._clear_commission(&vault_contract, from);
+        Ok(())
+    }
 
-        if from == contract::address() || to == contract::address() {
-            let _ = self._perform_transfer(vault_address, _owner, owner_commission);
+    /// Applies the pending vault update once its delay has passed. Only
+    /// callable by the owner.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller is not the owner, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If no update is pending, then the error
+    ///   [`Error::NoPendingVaultUpdate`] is returned.
+    /// * If the delay has not passed yet, then the error
+    ///   [`Error::VaultUpdateNotReady`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`VaultUpdated`] event.
+    pub fn execute_vault_update(&mut self) -> Result<(), Error> {
+        self._only_owner()?;
 
-            let _ = self._clear_commission(&vault_contract, _owner);
+        let new_vault = self.pending_vault.get();
+        if new_vault.is_zero() {
+            return Err(Error::NoPendingVaultUpdate(NoPendingVaultUpdate {}));
         }
-        return Ok(());
+        let eta = self.vault_update_eta.get();
+        if U256::from(block::timestamp()) < eta {
+            return Err(Error::VaultUpdateNotReady(VaultUpdateNotReady { eta }));
+        }
+
+        self._clear_pending_vault();
+        self._set_vault(new_vault);
+
+        Ok(())
+    }
+
+    /// Discards the pending vault update. Only callable by the owner.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller is not the owner, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If no update is pending, then the error
+    ///   [`Error::NoPendingVaultUpdate`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`VaultUpdateCanceled`] event.
+    pub fn cancel_vault_update(&mut self) -> Result<(), Error> {
+        self._only_owner()?;
+
+        let new_vault = self.pending_vault.get();
+        if new_vault.is_zero() {
+            return Err(Error::NoPendingVaultUpdate(NoPendingVaultUpdate {}));
+        }
+
+        self._clear_pending_vault();
+        evm::log(VaultUpdateCanceled { newVault: new_vault });
+
+        Ok(())
+    }
+
+    /// Sets the delay, in seconds, between scheduling and applying a vault
+    /// update. Zero applies updates at once. Only callable by the owner.
+    ///
+    /// An increase applies at once and discards any pending decrease. A
+    /// decrease is itself timelocked: it is scheduled behind the current
+    /// delay and applied with [`Self::execute_vault_delay_update`], so the
+    /// delay cannot be dropped to rotate the vault at once. The new delay
+    /// only affects updates scheduled afterwards.
+    ///
+    /// # Errors
+    ///
+    /// If the caller is not the owner, then the error
+    /// [`Error::UnauthorizedAccount`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`VaultDelayUpdated`] event, or a
+    /// [`VaultDelayUpdateScheduled`] event for a decrease.
+    pub fn set_vault_delay(&mut self, delay: U256) -> Result<(), Error> {
+        self._only_owner()?;
+
+        let current_delay = self.vault_delay.get();
+        if delay >= current_delay {
+            self._clear_pending_vault_delay();
+            self.vault_delay.set(delay);
+            evm::log(VaultDelayUpdated { delay });
+            return Ok(());
+        }
+
+        let eta = U256::from(block::timestamp()).saturating_add(current_delay);
+        self.pending_vault_delay.set(delay);
+        self.vault_delay_eta.set(eta);
+        evm::log(VaultDelayUpdateScheduled { delay, eta });
+
+        Ok(())
+    }
+
+    /// Applies the pending vault delay decrease once the current delay has
+    /// passed. Only callable by the owner.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller is not the owner, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If no decrease is pending, then the error
+    ///   [`Error::NoPendingVaultUpdate`] is returned.
+    /// * If the current delay has not passed yet, then the error
+    ///   [`Error::VaultUpdateNotReady`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`VaultDelayUpdated`] event.
+    pub fn execute_vault_delay_update(&mut self) -> Result<(), Error> {
+        self._only_owner()?;
+
+        let eta = self.vault_delay_eta.get();
+        if eta.is_zero() {
+            return Err(Error::NoPendingVaultUpdate(NoPendingVaultUpdate {}));
+        }
+        if U256::from(block::timestamp()) < eta {
+            return Err(Error::VaultUpdateNotReady(VaultUpdateNotReady { eta }));
+        }
+
+        let delay = self.pending_vault_delay.get();
+        self._clear_pending_vault_delay();
+        self.vault_delay.set(delay);
+        evm::log(VaultDelayUpdated { delay });
+
+        Ok(())
+    }
+
+    /// Returns the delay, in seconds, applied to vault updates.
+    pub fn vault_delay(&self) -> U256 {
+        self.vault_delay.get()
+    }
+
+    /// Returns the vault of the pending update, or `Address::ZERO` if none
+    /// is pending.
+    pub fn pending_vault(&self) -> Address {
+        self.pending_vault.get()
+    }
+
+    /// Returns the time from which the pending vault update can be applied.
+    pub fn vault_update_eta(&self) -> U256 {
+        self.vault_update_eta.get()
+    }
+
+    /// Returns the pending vault delay decrease and the time from which it
+    /// can be applied, or zeros if none is pending.
+    pub fn pending_vault_delay(&self) -> (U256, U256) {
+        (self.pending_vault_delay.get(), self.vault_delay_eta.get())
+    }
+
+    pub fn transfer(&mut self, to: Address, amount: U256) -> Result<bool, Error> {
+        let caller = self._msg_sender();
+
+        self._pay_commissions(to, caller)?;
+
+        // Perform the transfer
+        self._transfer(caller, to, amount)?;
+
+        Ok(true)
+    }
+
+    /// Transfers `amounts[i]` of the caller's ATON to `to[i]` for every `i`.
+    ///
+    /// Vault commissions are settled once for the caller and once for each
+    /// unique recipient.
+    ///
+    /// # Errors
+    ///
+    /// * If the arrays have different lengths, then the error
+    ///   [`Error::BatchLengthMismatch`] is returned.
+    /// * If any transfer fails, then the whole batch reverts with its error.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Transfer`] event per transfer.
+    pub fn batch_transfer(&mut self, to: Vec<Address>, amounts: Vec<U256>) -> Result<bool, Error> {
+        self._batch_transfer(self._msg_sender(), &to, &amounts)?;
+        Ok(true)
+    }
+
+    /// Executes each entry of `data` as a call to this contract, in order,
+    /// and returns the encoded result of each call.
+    ///
+    /// Every entry is dispatched through the contract's own router with the
+    /// caller's context, as if sent by the caller directly.
+    ///
+    /// # Errors
+    ///
+    /// If any call reverts or matches no function, then the error
+    /// [`Error::MulticallFailed`] is returned with its index and revert data,
+    /// and the whole multicall reverts.
+    pub fn multicall(&mut self, data: Vec<Bytes>) -> Result<Vec<Bytes>, Error> {
+        let mut results = Vec::with_capacity(data.len());
+        for (index, call) in data.iter().enumerate() {
+            let result = call.split_first_chunk::<4>().and_then(|(selector, input)| {
+                let selector = u32::from_be_bytes(*selector);
+                <Self as Router<Self>>::route(self, selector, input)
+            });
+            let reason = match result {
+                Some(Ok(output)) => {
+                    results.push(Bytes(output));
+                    continue;
+                }
+                Some(Err(reason)) => reason,
+                None => Vec::new(),
+            };
+            return Err(Error::MulticallFailed(MulticallFailed {
+                index: U256::from(index),
+                reason: reason.into(),
+            }));
+        }
+        Ok(results)
+    }
+
+    /// Mints ATON at the current exchange rate for the ETH sent by a stake
+    /// engine.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller does not hold `ENGINE_ROLE`, then the error
+    ///   [`Error::AccessControlUnauthorizedAccount`] is returned.
+    /// * If the mint exceeds the caller's remaining quota, then the error
+    ///   [`Error::MintQuotaExceeded`] is returned.
+    /// * If the exchange rate cannot be determined, then the errors of
+    ///   [`Self::exchange_rate`] are returned.
+    #[payable]
+    pub fn mint_aton(&mut self) -> Result<bool, Error> {
+        self.mint_aton_min_out(U256::ZERO)?;
+        Ok(true)
+    }
+
+    /// [`Self::mint_aton`] that reverts unless at least `min_out` ATON are
+    /// minted, and returns the amount minted.
+    ///
+    /// # Errors
+    ///
+    /// If fewer than `min_out` ATON would be minted, then the error
+    /// [`Error::SlippageExceeded`] is returned.
+    #[payable]
+    pub fn mint_aton_min_out(&mut self, min_out: U256) -> Result<U256, Error> {
+        self._non_reentrant_enter()?;
+        let result = self._mint_for_eth(self._msg_sender(), msg::value(), min_out);
+        self._non_reentrant_exit();
+        result
+    }
+
+    /// Burns `amount` ATON from the caller and pays out their ETH value at
+    /// the current exchange rate.
+    ///
+    /// # Errors
+    ///
+    /// * If the payout for `amount` is zero, then the error
+    ///   [`Error::ZeroSwapAmount`] is returned.
+    /// * If the caller holds less than `amount`, then the error
+    ///   [`Error::InsufficientSwapBalance`] is returned.
+    /// * If the contract holds less ETH than the payout, then the error
+    ///   [`Error::InsufficientLiquidity`] is returned.
+    /// * If the exchange rate cannot be determined, then the errors of
+    ///   [`Self::exchange_rate`] are returned.
+    /// * If the ETH payout fails, then the error [`Error::EthTransferFailed`]
+    ///   is returned.
+    pub fn swap(&mut self, amount: U256) -> Result<bool, Error> {
+        self._require_not_paused(PAUSE_SWAP)?;
+        self._non_reentrant_enter()?;
+        let result = self._swap(self._msg_sender(), amount, U256::ZERO);
+        self._non_reentrant_exit();
+        result?;
+
+        Ok(true)
+    }
+
+    /// [`Self::swap`] that reverts unless at least `min_out` wei are paid
+    /// out, and returns the amount paid.
+    ///
+    /// # Errors
+    ///
+    /// If less than `min_out` wei would be paid out, then the error
+    /// [`Error::SlippageExceeded`] is returned.
+    pub fn swap_min_out(&mut self, amount: U256, min_out: U256) -> Result<U256, Error> {
+        self._require_not_paused(PAUSE_SWAP)?;
+        self._non_reentrant_enter()?;
+        let result = self._swap(self._msg_sender(), amount, min_out);
+        self._non_reentrant_exit();
+        result
+    }
+
+    /// Escrows `amount` ATON from the caller in the redemption queue and
+    /// returns the ticket id. The ticket is paid at the exchange rate in
+    /// force when [`Self::fulfill_swaps`] processes it.
+    ///
+    /// # Errors
+    ///
+    /// * If `amount` is zero, then the error [`Error::ZeroSwapAmount`] is
+    ///   returned.
+    /// * If the caller holds less than `amount`, then the error
+    ///   [`Error::InsufficientSwapBalance`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`SwapRequested`] event.
+    pub fn request_swap(&mut self, amount: U256) -> Result<U256, Error> {
+        self._require_not_paused(PAUSE_SWAP)?;
+        let account = self._msg_sender();
+        if amount.is_zero() {
+            return Err(Error::ZeroSwapAmount(ZeroSwapAmount { account }));
+        }
+        let balance = self.spendable_balance_of(account);
+        if balance < amount {
+            return Err(Error::InsufficientSwapBalance(InsufficientSwapBalance {
+                account,
+                balance,
+                needed: amount,
+            }));
+        }
+
+        self._transfer(account, contract::address(), amount)?;
+
+        let id = self.swap_ticket_count.get() + U256::from(1);
+        self.swap_ticket_count.set(id);
+        let mut ticket = self.swap_tickets.setter(id);
+        ticket.owner.set(account);
+        ticket.amount.set(amount);
+        ticket.status.set(U8::from(TICKET_PENDING));
+        self.queued_aton.set(self.queued_aton.get() + amount);
+
+        evm::log(SwapRequested { id, owner: account, amount });
+
+        Ok(id)
+    }
+
+    /// Pays out queued tickets in FIFO order while the token holds enough
+    /// ETH, examining at most `max` tickets, and returns how many were
+    /// fulfilled.
+    ///
+    /// Processing stops at the first ticket that cannot be paid in full. A
+    /// ticket whose payout fails, or rounds down to zero, is canceled and
+    /// refunded so that it cannot block the queue.
+    ///
+    /// # Errors
+    ///
+    /// If the exchange rate cannot be determined, then the errors of
+    /// [`Self::exchange_rate`] are returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`SwapFulfilled`] or [`SwapCanceled`] event per ticket paid
+    /// or refunded.
+    pub fn fulfill_swaps(&mut self, max: U256) -> Result<U256, Error> {
+        self._require_not_paused(PAUSE_SWAP)?;
+        self._non_reentrant_enter()?;
+        let result = self._fulfill_swaps(max);
+        self._non_reentrant_exit();
+        result
+    }
+
+    /// Adds the ETH sent by a stake engine to the liquidity paying out swaps
+    /// and queued tickets, without minting ATON.
+    ///
+    /// # Errors
+    ///
+    /// If the caller does not hold `ENGINE_ROLE`, then the error
+    /// [`Error::AccessControlUnauthorizedAccount`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`ReserveUpdated`] event.
+    #[payable]
+    pub fn deposit_liquidity(&mut self) -> Result<(), Error> {
+        self._check_role(ENGINE_ROLE, self._msg_sender())?;
+
+        self.eth_deposited.set(self.eth_deposited.get() + msg::value());
+        self._log_reserve();
+
+        Ok(())
+    }
+
+    /// Cancels the caller's pending ticket `id` and refunds its ATON.
+    ///
+    /// # Errors
+    ///
+    /// * If ticket `id` is not pending, then the error
+    ///   [`Error::SwapTicketNotPending`] is returned.
+    /// * If the caller does not own the ticket, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`SwapCanceled`] event.
+    pub fn cancel_swap(&mut self, id: U256) -> Result<(), Error> {
+        let status = self.swap_tickets.get(id).status.get().to::<u8>();
+        if status != TICKET_PENDING {
+            return Err(Error::SwapTicketNotPending(SwapTicketNotPending { id, status }));
+        }
+        let caller = self._msg_sender();
+        if self.swap_tickets.get(id).owner.get() != caller {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
+                account: caller,
+            }));
+        }
+
+        self._refund_swap_ticket(id)
+    }
+
+    /// Returns the owner, escrowed ATON and `TICKET_*` status of swap ticket
+    /// `id`. Unknown tickets have a zero status.
+    pub fn swap_ticket(&self, id: U256) -> (Address, U256, u8) {
+        let ticket = self.swap_tickets.get(id);
+        (ticket.owner.get(), ticket.amount.get(), ticket.status.get().to::<u8>())
+    }
+
+    /// Returns the ids of the last ticket issued and of the last ticket the
+    /// queue has moved past. Tickets in between may still be pending.
+    pub fn swap_queue(&self) -> (U256, U256) {
+        (self.swap_ticket_count.get(), self.swap_queue_head.get())
+    }
+
+    /// Returns the ATON escrowed by pending tickets.
+    pub fn queued_swap_amount(&self) -> U256 {
+        self.queued_aton.get()
+    }
+
+    /// Pays `amounts[i]` of the caller's ATON to `recipients[i]` for every
+    /// `i`. Only callable by stake engines.
+    ///
+    /// Vault commissions are settled once for the caller and once for each
+    /// unique recipient, instead of once per transfer.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller does not hold `ENGINE_ROLE`, then the error
+    ///   [`Error::AccessControlUnauthorizedAccount`] is returned.
+    /// * If the arrays have different lengths, then the error
+    ///   [`Error::BatchLengthMismatch`] is returned.
+    /// * If any payout fails, then the whole batch reverts with its error.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Transfer`] event per payout and a [`BatchPayout`] summary.
+    pub fn batch_payout(
+        &mut self,
+        recipients: Vec<Address>,
+        amounts: Vec<U256>,
+    ) -> Result<bool, Error> {
+        let engine = self._msg_sender();
+        self._check_role(ENGINE_ROLE, engine)?;
+        let total = self._batch_transfer(engine, &recipients, &amounts)?;
+
+        evm::log(BatchPayout {
+            engine,
+            recipients: U256::from(recipients.len()),
+            total,
+        });
+
+        Ok(true)
+    }
+
+    /// Locks `amount` of `player`'s spendable balance for bet `bet_id`. Only
+    /// callable by stake engines, out of the allowance `player` granted the
+    /// engine.
+    ///
+    /// The ATON stays in the player's balance but cannot be moved until the
+    /// engine releases or settles the bet. The lock consumes the allowance as
+    /// `transfer_from` would, so an engine can never hold more of a player's
+    /// ATON than the player approved.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller does not hold `ENGINE_ROLE`, then the error
+    ///   [`Error::AccessControlUnauthorizedAccount`] is returned.
+    /// * If `amount` is zero, then the error [`Error::InvalidLockAmount`] is
+    ///   returned.
+    /// * If `bet_id` was already used, then the error
+    ///   [`Error::BetAlreadyLocked`] is returned.
+    /// * If `player` has less than `amount` spendable, then the error
+    ///   [`Error::InsufficientBalance`] is returned.
+    /// * If `player` allowed the engine less than `amount`, then the error
+    ///   [`Error::InsufficientAllowance`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`BalanceLocked`] event.
+    pub fn lock(&mut self, player: Address, amount: U256, bet_id: U256) -> Result<(), Error> {
+        let engine = self._msg_sender();
+        self._check_role(ENGINE_ROLE, engine)?;
+        if amount.is_zero() {
+            return Err(Error::InvalidLockAmount(InvalidLockAmount { amount }));
+        }
+        if !self.bet_locks.get(bet_id).engine.get().is_zero() {
+            return Err(Error::BetAlreadyLocked(BetAlreadyLocked { betId: bet_id }));
+        }
+        let spendable = self.spendable_balance_of(player);
+        if spendable < amount {
+            return Err(Error::InsufficientBalance(ERC20InsufficientBalance {
+                sender: player,
+                balance: spendable,
+                needed: amount,
+            }));
+        }
+        self._spend_allowance(player, engine, amount)?;
+
+        let mut bet = self.bet_locks.setter(bet_id);
+        bet.engine.set(engine);
+        bet.player.set(player);
+        bet.amount.set(amount);
+        bet.active.set(true);
+        let locked = self.locked_balances.get(player);
+        self.locked_balances.setter(player).set(locked + amount);
+
+        evm::log(BalanceLocked {
+            betId: bet_id,
+            engine,
+            player,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Releases the lock of bet `bet_id` without moving any ATON. Only
+    /// callable by the engine that created the lock, while it still holds
+    /// `ENGINE_ROLE`.
+    ///
+    /// # Errors
+    ///
+    /// * If the bet has no active lock, then the error
+    ///   [`Error::BetNotLocked`] is returned.
+    /// * If the caller did not create the lock, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If the caller no longer holds `ENGINE_ROLE`, then the error
+    ///   [`Error::AccessControlUnauthorizedAccount`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`LockReleased`] event.
+    pub fn release(&mut self, bet_id: U256) -> Result<(), Error> {
+        let (player, amount) = self._unlock(bet_id)?;
+        evm::log(LockReleased {
+            betId: bet_id,
+            player,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Settles bet `bet_id` by moving `payout` of the locked ATON from the
+    /// player to `winner` and unlocking the rest. Only callable by the engine
+    /// that created the lock, while it still holds `ENGINE_ROLE`.
+    ///
+    /// # Errors
+    ///
+    /// * If the bet has no active lock, then the error
+    ///   [`Error::BetNotLocked`] is returned.
+    /// * If the caller did not create the lock, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If the caller no longer holds `ENGINE_ROLE`, then the error
+    ///   [`Error::AccessControlUnauthorizedAccount`] is returned.
+    /// * If `payout` exceeds the locked amount, then the error
+    ///   [`Error::PayoutExceedsLock`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`BetSettled`] event, and a [`Transfer`] event if `payout`
+    /// is not zero.
+    pub fn settle(&mut self, bet_id: U256, winner: Address, payout: U256) -> Result<(), Error> {
+        let (player, locked) = self._check_unlock(bet_id)?;
+        if payout > locked {
+            return Err(Error::PayoutExceedsLock(PayoutExceedsLock { payout, locked }));
+        }
+
+        self._clear_lock(bet_id, player, locked);
+        if !payout.is_zero() {
+            self._transfer(player, winner, payout)?;
+        }
+
+        evm::log(BetSettled {
+            betId: bet_id,
+            player,
+            winner,
+            payout,
+        });
+
+        Ok(())
+    }
+
+    /// Releases the lock of bet `bet_id` without moving any ATON, whichever
+    /// engine created it. Only callable by holders of `DEFAULT_ADMIN_ROLE`,
+    /// so locks left by a revoked engine do not freeze the player's ATON.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller does not hold `DEFAULT_ADMIN_ROLE`, then the error
+    ///   [`Error::AccessControlUnauthorizedAccount`] is returned.
+    /// * If the bet has no active lock, then the error
+    ///   [`Error::BetNotLocked`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`LockReleased`] event.
+    pub fn force_release(&mut self, bet_id: U256) -> Result<(), Error> {
+        self._check_role(DEFAULT_ADMIN_ROLE, self._msg_sender())?;
+        let bet = self.bet_locks.get(bet_id);
+        let (player, amount) = (bet.player.get(), bet.amount.get());
+        if !bet.active.get() {
+            return Err(Error::BetNotLocked(BetNotLocked { betId: bet_id }));
+        }
+
+        self._clear_lock(bet_id, player, amount);
+        evm::log(LockReleased {
+            betId: bet_id,
+            player,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the part of `account`'s balance held by active bet locks.
+    pub fn locked_balance_of(&self, account: Address) -> U256 {
+        self.locked_balances.get(account)
+    }
+
+    /// Returns the part of `account`'s balance that can be transferred.
+    pub fn spendable_balance_of(&self, account: Address) -> U256 {
+        self.balances
+            .get(account)
+            .saturating_sub(self.locked_balances.get(account))
+    }
+
+    /// Returns the engine, player, amount and active flag of the lock of bet
+    /// `bet_id`.
+    pub fn bet_lock(&self, bet_id: U256) -> (Address, Address, U256, bool) {
+        let bet = self.bet_locks.get(bet_id);
+        (bet.engine.get(), bet.player.get(), bet.amount.get(), bet.active.get())
+    }
+
+    /// Returns the ATON minted for `eth_amount` wei at the current rate.
+    ///
+    /// # Errors
+    ///
+    /// * If the exchange rate cannot be determined, then the errors of
+    ///   [`Self::exchange_rate`] are returned.
+    /// * If the quote overflows, then the error [`Error::QuoteOverflow`] is
+    ///   returned.
+    pub fn quote_mint(&self, eth_amount: U256) -> Result<U256, Error> {
+        let rate = self.exchange_rate()?;
+        eth_amount
+            .checked_mul(rate)
+            .and_then(|scaled| scaled.checked_div(RATE_PRECISION))
+            .ok_or(Error::QuoteOverflow(QuoteOverflow { amount: eth_amount, rate }))
+    }
+
+    /// Returns the wei paid out for swapping `aton_amount` ATON at the
+    /// current rate.
+    ///
+    /// # Errors
+    ///
+    /// * If the exchange rate cannot be determined, then the errors of
+    ///   [`Self::exchange_rate`] are returned.
+    /// * If the quote overflows, then the error [`Error::QuoteOverflow`] is
+    ///   returned.
+    pub fn quote_swap(&self, aton_amount: U256) -> Result<U256, Error> {
+        let rate = self.exchange_rate()?;
+        aton_amount
+            .checked_mul(RATE_PRECISION)
+            .and_then(|scaled| scaled.checked_div(rate))
+            .ok_or(Error::QuoteOverflow(QuoteOverflow { amount: aton_amount, rate }))
+    }
+
+    /// Returns the ATON minted per ETH, scaled by `RATE_PRECISION`.
+    ///
+    /// The price feed is used when one is set. Otherwise the configured
+    /// rate applies, one-to-one until the owner sets one.
+    ///
+    /// # Errors
+    ///
+    /// * If the price feed cannot be read, then the error
+    ///   [`Error::PriceFeedCallFailed`] is returned.
+    /// * If the feed answer is not positive, or too large to scale with its
+    ///   decimals, then the error [`Error::InvalidPrice`] is returned.
+    /// * If the feed answer was never updated or is older than
+    ///   `max_price_age`, then the error [`Error::StalePrice`] is returned.
+    /// * If the rate rounds down to zero, then the error
+    ///   [`Error::InvalidExchangeRate`] is returned.
+    pub fn exchange_rate(&self) -> Result<U256, Error> {
+        let feed = self.price_feed.get();
+        if feed.is_zero() {
+            let rate = self.exchange_rate.get();
+            return Ok(if rate.is_zero() { RATE_PRECISION } else { rate });
+        }
+
+        let (answer, decimals) = self._fresh_price(feed, self.max_price_age.get())?;
+        let rate = answer
+            .into_raw()
+            .checked_mul(RATE_PRECISION)
+            .and_then(|scaled| {
+                let unit = U256::from(10).checked_pow(U256::from(decimals))?;
+                Some(scaled / unit)
+            })
+            .ok_or(Error::InvalidPrice(InvalidPrice { answer }))?;
+        if rate.is_zero() {
+            return Err(Error::InvalidExchangeRate(InvalidExchangeRate { rate }));
+        }
+
+        Ok(rate)
+    }
+
+    /// Sets the ATON minted per ETH, scaled by `RATE_PRECISION`. Only
+    /// callable by the owner. Ignored while a price feed is set.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller is not the owner, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If `rate` is zero, then the error [`Error::InvalidExchangeRate`] is
+    ///   returned.
+    ///
+    /// # Events
+    ///
+    /// Emits an [`ExchangeRateUpdated`] event.
+    pub fn set_exchange_rate(&mut self, rate: U256) -> Result<(), Error> {
+        self._only_owner()?;
+        if rate.is_zero() {
+            return Err(Error::InvalidExchangeRate(InvalidExchangeRate { rate }));
+        }
+
+        self.exchange_rate.set(rate);
+        evm::log(ExchangeRateUpdated { rate });
+
+        Ok(())
+    }
+
+    /// Sets the ERC-2771 trusted forwarder. Calls from the forwarder are
+    /// attributed to the sender it appends to the calldata. `Address::ZERO`
+    /// disables meta-transactions. Only callable by the owner.
+    ///
+    /// # Errors
+    ///
+    /// If the caller is not the owner, then the error
+    /// [`Error::UnauthorizedAccount`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`TrustedForwarderUpdated`] event.
+    pub fn set_trusted_forwarder(&mut self, forwarder: Address) -> Result<(), Error> {
+        self._only_owner()?;
+
+        let previous = self.trusted_forwarder.get();
+        self.trusted_forwarder.set(forwarder);
+        evm::log(TrustedForwarderUpdated {
+            previousForwarder: previous,
+            newForwarder: forwarder,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the ERC-2771 trusted forwarder, or zero if none is set.
+    pub fn trusted_forwarder(&self) -> Address {
+        self.trusted_forwarder.get()
+    }
+
+    /// Returns whether `forwarder` is the ERC-2771 trusted forwarder.
+    pub fn is_trusted_forwarder(&self, forwarder: Address) -> bool {
+        !forwarder.is_zero() && forwarder == self.trusted_forwarder.get()
+    }
+
+    /// Sets the price feed providing the exchange rate, and the maximum age
+    /// in seconds of its answers. `Address::ZERO` returns to the configured
+    /// rate. Only callable by the owner.
+    ///
+    /// A new feed must already report a usable answer.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller is not the owner, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If `feed` is set with a zero `max_age`, then the error
+    ///   [`Error::InvalidMaxPriceAge`] is returned.
+    /// * If the feed's latest answer cannot be used, then the errors of
+    ///   [`Self::exchange_rate`] are returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`PriceFeedUpdated`] event.
+    pub fn set_price_feed(&mut self, feed: Address, max_age: U256) -> Result<(), Error> {
+        self._only_owner()?;
+        if !feed.is_zero() {
+            if max_age.is_zero() {
+                return Err(Error::InvalidMaxPriceAge(InvalidMaxPriceAge { maxAge: max_age }));
+            }
+            self._fresh_price(feed, max_age)?;
+        }
+
+        self.price_feed.set(feed);
+        self.max_price_age.set(max_age);
+        evm::log(PriceFeedUpdated { feed, maxAge: max_age });
+
+        Ok(())
+    }
+
+    /// Returns the ETH needed to redeem `total_supply` ATON at the current
+    /// exchange rate.
+    ///
+    /// # Errors
+    ///
+    /// If the exchange rate cannot be determined, then the errors of
+    /// [`Self::exchange_rate`] are returned.
+    pub fn backing_of(&self, total_supply: U256) -> Result<U256, Error> {
+        self.quote_swap(total_supply)
+    }
+
+    /// Returns the ETH the token must keep to back the supply: the ETH
+    /// deposited and not yet paid out through swaps, or the ETH needed to
+    /// redeem the whole supply at the current rate if that is more. Raising
+    /// the rate never lowers it below the tracked deposits while ATON is
+    /// outstanding. Once the whole supply has been redeemed nothing is left
+    /// to back, so deposits that swaps did not pay out can be swept.
+    ///
+    /// # Errors
+    ///
+    /// If the exchange rate cannot be determined, then the errors of
+    /// [`Self::exchange_rate`] are returned.
+    pub fn required_reserve(&self) -> Result<U256, Error> {
+        let total_supply = self.total_supply.get();
+        if total_supply.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let deposits = self.eth_deposited.get().saturating_sub(self.eth_paid_out.get());
+        let backing = self.backing_of(total_supply)?;
+        Ok(deposits.max(backing))
+    }
+
+    /// Returns the ETH held by the token divided by
+    /// [`Self::required_reserve`], scaled by `RATE_PRECISION`. `U256::MAX`
+    /// when there is nothing to back.
+    ///
+    /// # Errors
+    ///
+    /// If the exchange rate cannot be determined, then the errors of
+    /// [`Self::exchange_rate`] are returned.
+    pub fn reserve_ratio(&self) -> Result<U256, Error> {
+        let required = self.required_reserve()?;
+        if required.is_zero() {
+            return Ok(U256::MAX);
+        }
+
+        Ok(contract::balance() * RATE_PRECISION / required)
+    }
+
+    /// Returns the ETH held beyond [`Self::required_reserve`].
+    ///
+    /// # Errors
+    ///
+    /// If the exchange rate cannot be determined, then the errors of
+    /// [`Self::exchange_rate`] are returned.
+    pub fn excess_eth(&self) -> Result<U256, Error> {
+        Ok(contract::balance().saturating_sub(self.required_reserve()?))
+    }
+
+    /// Returns the total ETH deposited through mints and liquidity deposits,
+    /// paid out through swaps, and swept as excess, in that order.
+    pub fn reserve_totals(&self) -> (U256, U256, U256) {
+        (
+            self.eth_deposited.get(),
+            self.eth_paid_out.get(),
+            self.eth_swept.get(),
+        )
+    }
+
+    /// Sends `amount` wei of excess ETH to `to`. Only callable by the owner.
+    ///
+    /// The ETH left behind always covers [`Self::required_reserve`], so
+    /// changing the exchange rate cannot free deposited ETH.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller is not the owner, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If `to` is `Address::ZERO`, then the error [`Error::Zero`] is
+    ///   returned.
+    /// * If `amount` exceeds [`Self::excess_eth`], then the error
+    ///   [`Error::InsufficientExcess`] is returned.
+    /// * If the transfer fails, then the error [`Error::EthTransferFailed`] is
+    ///   returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`ReserveUpdated`] event.
+    pub fn sweep_excess(&mut self, to: Address, amount: U256) -> Result<(), Error> {
+        self._only_owner()?;
+        if to.is_zero() {
+            return Err(Error::Zero(Zero { account: to }));
+        }
+
+        self._non_reentrant_enter()?;
+        let result = self._sweep_excess(to, amount);
+        self._non_reentrant_exit();
+        result
+    }
+
+    /// Returns the price feed providing the exchange rate, if any.
+    pub fn price_feed(&self) -> Address {
+        self.price_feed.get()
+    }
+
+    /// Returns the maximum age in seconds of a price feed answer.
+    pub fn max_price_age(&self) -> U256 {
+        self.max_price_age.get()
+    }
+
+    /// Grants or revokes `ENGINE_ROLE` for `account`. Only callable by
+    /// holders of the engine role's admin role.
+    pub fn update_stake_engine(&mut self, account: Address, status: bool) -> Result<(), Error> {
+        self._check_role(self.role_admins.get(ENGINE_ROLE), self._msg_sender())?;
+
+        if status {
+            self._grant_role(ENGINE_ROLE, account);
+        } else {
+            self._revoke_role(ENGINE_ROLE, account);
+        }
+
+        // Emit an event (optional, but recommended for transparency)
+        evm::log(EngineUpdated { account, status });
+
+        Ok(())
+    }
+
+    /// Returns whether `account` holds `ENGINE_ROLE`.
+    pub fn is_stake_engine(&self, account: Address) -> bool {
+        self.has_role(ENGINE_ROLE, account)
+    }
+
+    /// Returns the maximum amount of `token` available for a flash loan.
+    ///
+    /// Loans are capped so that neither the fee nor the repayment can
+    /// overflow.
+    pub fn max_flash_loan(&self, token: Address) -> U256 {
+        if token != contract::address() {
+            return U256::ZERO;
+        }
+        let cap = U256::MAX / U256::from(FLASH_FEE_DENOMINATOR);
+        (U256::MAX - self.total_supply.get()).min(cap)
+    }
+
+    /// Returns the fee charged for a flash loan of `amount` `token`.
+    ///
+    /// # Errors
+    ///
+    /// * If `token` is not ATON, then the error [`Error::UnsupportedToken`]
+    ///   is returned.
+    /// * If the fee overflows, then the error [`Error::ExceededMaxLoan`] is
+    ///   returned.
+    pub fn flash_fee(&self, token: Address, amount: U256) -> Result<U256, Error> {
+        if token != contract::address() {
+            return Err(Error::UnsupportedToken(ERC3156UnsupportedToken { token }));
+        }
+        let fee = amount.checked_mul(self.flash_fee_bps.get()).ok_or_else(|| {
+            Error::ExceededMaxLoan(ERC3156ExceededMaxLoan {
+                maxLoan: self.max_flash_loan(token),
+            })
+        })?;
+        Ok(fee / U256::from(FLASH_FEE_DENOMINATOR))
+    }
+
+    /// Mints `amount` ATON to `receiver`, calls its `onFlashLoan` hook and
+    /// then takes back `amount` plus the fee through the allowance
+    /// `receiver` granted this contract (ERC-3156).
+    ///
+    /// The hook may call back into the token, to use the loan and to approve
+    /// the repayment. The reentrancy guard is held for the whole loan, so the
+    /// hook cannot mint or swap. Its transfers still settle vault
+    /// commissions, under the guard the loan holds. The principal
+    /// is burnt and the fee is sent to the vault, or burnt when no vault is
+    /// set.
+    ///
+    /// # Errors
+    ///
+    /// * If minting is paused, then the error [`Error::EnforcedPause`] is
+    ///   returned.
+    /// * If the call re-enters a guarded function, then the error
+    ///   [`Error::ReentrantCall`] is returned.
+    /// * If `token` is not ATON, then the error [`Error::UnsupportedToken`]
+    ///   is returned.
+    /// * If `amount` exceeds [`Self::max_flash_loan`], then the error
+    ///   [`Error::ExceededMaxLoan`] is returned.
+    /// * If `receiver` does not return `FLASH_LOAN_CALLBACK_SUCCESS`, then the
+    ///   error [`Error::InvalidFlashReceiver`] is returned.
+    /// * If `receiver` did not approve the repayment, then the error
+    ///   [`Error::InsufficientAllowance`] is returned.
+    pub fn flash_loan(
+        &mut self,
+        receiver: Address,
+        token: Address,
+        amount: U256,
+        data: Bytes,
+    ) -> Result<bool, Error> {
+        self._require_not_paused(PAUSE_MINT)?;
+        self._non_reentrant_enter()?;
+        self.flash_loan_active.set(true);
+        let result = self._flash_loan(receiver, token, amount, data);
+        self.flash_loan_active.set(false);
+        self._non_reentrant_exit();
+        result?;
+
+        Ok(true)
+    }
+
+    /// Sets the flash loan fee in basis points. Only callable by the owner.
+    ///
+    /// # Errors
+    ///
+    /// If `fee_bps` exceeds 10 000, then the error [`Error::FlashFeeTooHigh`]
+    /// is returned.
+    pub fn set_flash_fee(&mut self, fee_bps: U256) -> Result<(), Error> {
+        self._only_owner()?;
+        if fee_bps > U256::from(FLASH_FEE_DENOMINATOR) {
+            return Err(Error::FlashFeeTooHigh(FlashFeeTooHigh { feeBps: fee_bps }));
+        }
+
+        self.flash_fee_bps.set(fee_bps);
+        evm::log(FlashFeeUpdated { feeBps: fee_bps });
+
+        Ok(())
+    }
+
+    /// Activates the pause `flags`: `1` for transfers, `2` for `mint_aton`
+    /// and `4` for `swap`. Flags can be combined.
+    ///
+    /// Only callable by `PAUSER_ROLE` holders other than the owner. This keeps
+    /// pausing a separate duty, but the owner still administers
+    /// `PAUSER_ROLE` and can grant it to another account.
+    ///
+    /// # Errors
+    ///
+    /// If `flags` contains bits other than the known flags, then the error
+    /// [`Error::InvalidPauseFlags`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Paused`] event.
+    pub fn pause(&mut self, flags: u8) -> Result<(), Error> {
+        let account = self._only_pauser()?;
+        self._check_pause_flags(flags)?;
+
+        self.paused.set(U8::from(self.paused() | flags));
+        evm::log(Paused { account, flags });
+
+        Ok(())
+    }
+
+    /// Lifts the pause `flags`. Same access rules and errors as
+    /// [`Self::pause`].
+    ///
+    /// # Events
+    ///
+    /// Emits an [`Unpaused`] event.
+    pub fn unpause(&mut self, flags: u8) -> Result<(), Error> {
+        let account = self._only_pauser()?;
+        self._check_pause_flags(flags)?;
+
+        self.paused.set(U8::from(self.paused() & !flags));
+        evm::log(Unpaused { account, flags });
+
+        Ok(())
+    }
+
+    /// Returns the bitmask of active pause flags.
+    pub fn paused(&self) -> u8 {
+        self.paused.get().to::<u8>()
+    }
+
+    /// Returns whether `account` has been granted `role`.
+    pub fn has_role(&self, role: B256, account: Address) -> bool {
+        self.roles.getter(role).get(account)
+    }
+
+    /// Returns the admin role that controls `role`.
+    pub fn get_role_admin(&self, role: B256) -> B256 {
+        self.role_admins.get(role)
+    }
+
+    /// Grants `role` to `account`. Only callable by holders of the admin
+    /// role of `role`.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`RoleGranted`] event if `account` did not hold `role`.
+    pub fn grant_role(&mut self, role: B256, account: Address) -> Result<(), Error> {
+        self._check_role(self.role_admins.get(role), self._msg_sender())?;
+        self._grant_role(role, account);
+        Ok(())
+    }
+
+    /// Revokes `role` from `account`. Only callable by holders of the admin
+    /// role of `role`.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`RoleRevoked`] event if `account` held `role`.
+    pub fn revoke_role(&mut self, role: B256, account: Address) -> Result<(), Error> {
+        self._check_role(self.role_admins.get(role), self._msg_sender())?;
+        self._revoke_role(role, account);
+        Ok(())
+    }
+
+    /// Revokes `role` from the caller, who must pass its own address as
+    /// `caller_confirmation`.
+    ///
+    /// # Errors
+    ///
+    /// If `caller_confirmation` is not the caller, then the error
+    /// [`Error::AccessControlBadConfirmation`] is returned.
+    pub fn renounce_role(&mut self, role: B256, caller_confirmation: Address) -> Result<(), Error> {
+        if caller_confirmation != self._msg_sender() {
+            return Err(Error::AccessControlBadConfirmation(AccessControlBadConfirmation {}));
+        }
+
+        self._revoke_role(role, caller_confirmation);
+        Ok(())
+    }
+
+    /// Sets `admin_role` as the admin role of `role`. Only callable by holders
+    /// of `DEFAULT_ADMIN_ROLE`.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`RoleAdminChanged`] event.
+    pub fn set_role_admin(&mut self, role: B256, admin_role: B256) -> Result<(), Error> {
+        self._check_role(DEFAULT_ADMIN_ROLE, self._msg_sender())?;
+
+        let previous_admin_role = self.role_admins.get(role);
+        self.role_admins.setter(role).set(admin_role);
+        evm::log(RoleAdminChanged {
+            role,
+            previousAdminRole: previous_admin_role,
+            newAdminRole: admin_role,
+        });
+
+        Ok(())
+    }
+
+    /// Role that administers every other role by default.
+    #[selector(name = "DEFAULT_ADMIN_ROLE")]
+    pub fn default_admin_role(&self) -> B256 {
+        DEFAULT_ADMIN_ROLE
+    }
+
+    /// Role held by Arenaton stake engines.
+    #[selector(name = "ENGINE_ROLE")]
+    pub fn engine_role(&self) -> B256 {
+        ENGINE_ROLE
+    }
+
+    /// Role allowed to pause the token.
+    #[selector(name = "PAUSER_ROLE")]
+    pub fn pauser_role(&self) -> B256 {
+        PAUSER_ROLE
+    }
+
+    /// Role allowed to configure the vault.
+    #[selector(name = "VAULT_MANAGER_ROLE")]
+    pub fn vault_manager_role(&self) -> B256 {
+        VAULT_MANAGER_ROLE
+    }
+
+    /// Role allowed to manage engine mint quotas.
+    #[selector(name = "MINTER_ROLE")]
+    pub fn minter_role(&self) -> B256 {
+        MINTER_ROLE
+    }
+
+    /// Distributes `amount` of the caller's tokens as commission to every
+    /// holder, pro rata to their balance.
+    ///
+    /// The tokens are held by the contract until holders claim them through
+    /// [`Self::claim_commission`].
+    ///
+    /// # Errors
+    ///
+    /// * If `amount` is zero or no tokens are eligible for commission, then
+    ///   the error [`Error::Zero`] is returned.
+    /// * If the caller doesn't have enough tokens, then the error
+    ///   [`Error::InsufficientBalance`] is returned.
+    /// * If the commission per token would overflow, then the error
+    ///   [`Error::CommissionOverflow`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Transfer`] and a [`CommissionAccumulate`] event.
+    pub fn accumulate_commission(&mut self, amount: U256) -> Result<(), Error> {
+        let sender = self._msg_sender();
+        if amount.is_zero() {
+            return Err(Error::Zero(Zero { account: sender }));
+        }
+
+        self._update(sender, contract::address(), amount)?;
+
+        // Tokens held by the contract are the commission pool itself
+        let eligible_supply = self.total_supply.get() - self.balances.get(contract::address());
+        if eligible_supply.is_zero() {
+            return Err(Error::Zero(Zero { account: sender }));
+        }
+
+        let overflow = Error::CommissionOverflow(CommissionOverflow { account: sender });
+        let acc_per_token = amount
+            .checked_mul(COMMISSION_PRECISION)
+            .and_then(|scaled| {
+                self.acc_commission_per_token
+                    .get()
+                    .checked_add(scaled / eligible_supply)
+            })
+            .ok_or(overflow)?;
+        let total_commission = self.total_commission.get() + amount;
+        self.acc_commission_per_token.set(acc_per_token);
+        self.total_commission.set(total_commission);
+
+        evm::log(CommissionAccumulate {
+            amount,
+            newAccPerToken: acc_per_token,
+            totalCommission: total_commission,
+        });
+
+        Ok(())
+    }
+
+    /// Transfers the commission accrued by the caller to the caller.
+    ///
+    /// Returns the amount claimed.
+    ///
+    /// # Errors
+    ///
+    /// If the caller's commission overflows, then the error
+    /// [`Error::CommissionOverflow`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Transfer`] and a [`CommissionClaimed`] event when the
+    /// claimed amount is not zero.
+    pub fn claim_commission(&mut self) -> Result<U256, Error> {
+        let account = self._msg_sender();
+        self._checkpoint_commission(account)?;
+
+        let amount = self.commission_credit.get(account);
+        if amount.is_zero() {
+            return Ok(amount);
+        }
+
+        self.commission_credit.setter(account).set(U256::ZERO);
+        self._update(contract::address(), account, amount)?;
+        evm::log(CommissionClaimed { account, amount });
+
+        Ok(amount)
+    }
+
+    /// Returns the commission `account` can currently claim.
+    ///
+    /// # Errors
+    ///
+    /// If the commission overflows, then the error
+    /// [`Error::CommissionOverflow`] is returned.
+    pub fn pending_commission(&self, account: Address) -> Result<U256, Error> {
+        self.commission_credit
+            .get(account)
+            .checked_add(self._unsettled_commission(account)?)
+            .ok_or(Error::CommissionOverflow(CommissionOverflow { account }))
+    }
+
+    /// Returns the commission accumulated per eligible token, scaled by 1e18.
+    pub fn acc_commission_per_token(&self) -> U256 {
+        self.acc_commission_per_token.get()
+    }
+
+    /// Allows vault managers to switch vault commission settlement between
+    /// strict (fail-closed) and lenient (skip failures) mode.
+    pub fn set_strict_settlement(&mut self, strict: bool) -> Result<(), Error> {
+        self._check_role(VAULT_MANAGER_ROLE, self._msg_sender())?;
+
+        self.strict_settlement.set(strict);
+        evm::log(SettlementModeUpdated { strict });
+
+        Ok(())
+    }
+
+    /// Returns whether vault commission settlement fails closed.
+    pub fn strict_settlement(&self) -> bool {
+        self.strict_settlement.get()
+    }
+
+    /// Allows minters to set how many more tokens `engine` may mint.
+    pub fn set_mint_quota(&mut self, engine: Address, quota: U256) -> Result<(), Error> {
+        self._check_role(MINTER_ROLE, self._msg_sender())?;
+
+        self.mint_quota.setter(engine).set(quota);
+        evm::log(MintQuotaUpdated { engine, quota });
+
+        Ok(())
+    }
+
+    /// Returns how many tokens `engine` may still mint.
+    pub fn remaining_mint_quota(&self, engine: Address) -> U256 {
+        self.mint_quota.get(engine)
+    }
+}
+
+// Private Functions
+impl Erc20Aton {
+    /// Sets a `value` number of tokens as the allowance of `spender` over the
+    /// caller's tokens.
+    ///
+    /// Returns a boolean value indicating whether the operation succeeded.
+    /// # Arguments
+    ///
+    /// * `&mut self` - Write access to the contract's state.
+    /// * `owner` - Account that owns the tokens.
+    /// * `spender` - Account that will spend the tokens.
+    /// * `emit_event` - Emit an [`Approval`] event flag.
+    ///
+    /// # Errors
+    ///
+    /// If the `spender` address is `Address::ZERO`, then the error
+    /// [`Error::InvalidSpender`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits an [`Approval`] event.
+    fn _approve(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        emit_event: bool,
+    ) -> Result<bool, Error> {
+        if owner.is_zero() {
+            return Err(Error::InvalidApprover(ERC20InvalidApprover {
+                approver: Address::ZERO,
+            }));
+        }
+
+        if spender.is_zero() {
+            return Err(Error::InvalidSpender(ERC20InvalidSpender {
+                spender: Address::ZERO,
+            }));
+        }
+
+        self.allowances.setter(owner).insert(spender, value);
+        if emit_event {
+            evm::log(Approval {
+                owner,
+                spender,
+                value,
+            });
+        }
+        Ok(true)
+    }
+    /// Internal implementation of transferring tokens between two accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - Write access to the contract's state.
+    /// * `from` - Account to transfer tokens from.
+    /// * `to` - Account to transfer tokens to.
+    /// * `value` - The number of tokens to transfer.
+    ///
+    /// # Errors
+    ///
+    /// * If the `from` address is `Address::ZERO`, then the error
+    ///   [`Error::InvalidSender`] is returned.
+    /// * If the `to` address is `Address::ZERO`, then the error
+    ///   [`Error::InvalidReceiver`] is returned.
+    /// * If the `from` address doesn't have enough tokens, then the error
+    ///   [`Error::InsufficientBalance`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Transfer`] event.
+    fn _transfer(&mut self, from: Address, to: Address, value: U256) -> Result<(), Error> {
+        if from.is_zero() {
+            return Err(Error::InvalidSender(ERC20InvalidSender {
+                sender: Address::ZERO,
+            }));
+        }
+        if to.is_zero() {
+            return Err(Error::InvalidReceiver(ERC20InvalidReceiver {
+                receiver: Address::ZERO,
+            }));
+        }
+
+        self._update(from, to, value)?;
+
+        Ok(())
+    }
+
+    /// Destroys a `value` amount of tokens from `account`,
+    /// lowering the total supply.
+    ///
+    /// Relies on the `update` mechanism.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - Owner's address.
+    /// * `value` - Amount to be burnt.
+    ///
+    /// # Errors
+    ///
+    /// * If the `from` address is `Address::ZERO`, then the error
+    ///   [`Error::InvalidSender`] is returned.
+    /// * If the `from` address doesn't have enough tokens, then the error
+    ///   [`Error::InsufficientBalance`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Transfer`] event.
+    pub fn _burn(&mut self, account: Address, value: U256) -> Result<(), Error> {
+        if account == Address::ZERO {
+            return Err(Error::InvalidSender(ERC20InvalidSender {
+                sender: Address::ZERO,
+            }));
+        }
+        self._update(account, Address::ZERO, value)
+    }
+
+    /// Updates `owner`'s allowance for `spender` based on spent `value`.
+    ///
+    /// Does not update the allowance value in the case of infinite allowance.
+    ///
+    /// # Arguments
+    ///
+    /// * `&mut self` - Write access to the contract's state.
+    /// * `owner` - Account to transfer tokens from.
+    /// * `spender` - Account spending the allowance.
+    /// * `value` - The number of tokens to transfer.
+    ///
+    /// # Errors
+    ///
+    /// If not enough allowance is available, then the error
+    /// [`Error::InsufficientAllowance`] is returned.
+    pub fn _spend_allowance(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+    ) -> Result<(), Error> {
+        let current_allowance = self.allowance(owner, spender);
+        if current_allowance != U256::MAX {
+            if current_allowance < value {
+                return Err(Error::InsufficientAllowance(
+                    ERC20InsufficientAllowance {
+                        spender,
+                        allowance: current_allowance,
+                        needed: value,
+                    },
+                ));
+            }
+
+            self._approve(owner, spender, current_allowance - value, false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates a `value` amount of tokens and assigns them to `account`,
+    /// by transferring it from `Address::ZERO`.
+    ///
+    /// Relies on the `_update` mechanism.
+    ///
+    /// # Panics
+    ///
+    /// If `total_supply` exceeds `U256::MAX`.
+    ///
+    /// # Errors
+    ///
+    /// If the `account` address is `Address::ZERO`, then the error
+    /// [`Error::InvalidReceiver`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Transfer`] event.
+    pub fn _mint(&mut self, account: Address, value: U256) -> Result<(), Error> {
+        if account.is_zero() {
+            return Err(Error::InvalidReceiver(ERC20InvalidReceiver {
+                receiver: Address::ZERO,
+            }));
+        }
+        self._update(Address::ZERO, account, value)
+    }
+    /// Transfers a `value` amount of tokens from `from` to `to`, or
+    /// alternatively mints (or burns) if `from` (or `to`) is the zero address.
+    ///
+    /// All customizations to transfers, mints, and burns should be done by
+    /// using this function.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Owner's address.
+    /// * `to` - Recipient's address.
+    /// * `value` - Amount to be transferred.
+    ///
+    /// # Panics
+    ///
+    /// If `total_supply` exceeds `U256::MAX`. It may happen during `mint`
+    /// operation.
+    ///
+    /// # Errors
+    ///
+    /// If the `from` address doesn't have enough tokens, then the error
+    /// [`Error::InsufficientBalance`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Transfer`] event.
+    pub fn _update(&mut self, from: Address, to: Address, value: U256) -> Result<(), Error> {
+        self._require_not_paused(PAUSE_TRANSFERS)?;
+
+        // Settle commission at the old balances before they change
+        self._checkpoint_commission(from)?;
+        self._checkpoint_commission(to)?;
+        self._update_snapshots(from, to);
+
+        if from.is_zero() {
+            // Mint operation. Overflow check required: the rest of the code
+            // assumes that `total_supply` never overflows.
+            let current_supply = self.total_supply.get();
+            let new_supply = current_supply.checked_add(value).ok_or(
+                Error::InsufficientBalance(ERC20InsufficientBalance {
+                    sender: from,
+                    balance: current_supply,
+                    needed: value,
+                }),
+            )?;
+            self.total_supply.set(new_supply);
+        } else {
+            // Check the `from` balance before deduction, leaving bet locks untouched
+            let from_balance = self.balances.get(from);
+            let spendable = from_balance.saturating_sub(self.locked_balances.get(from));
+            if spendable < value {
+                return Err(Error::InsufficientBalance(ERC20InsufficientBalance {
+                    sender: from,
+                    balance: spendable,
+                    needed: value,
+                }));
+            }
+            // Safely decrease the `from` balance
+            self.balances.setter(from).set(from_balance - value);
+        }
+
+        if to.is_zero() {
+            // Burn operation: decrease total supply
+            let current_supply = self.total_supply.get();
+            let new_supply = current_supply.checked_sub(value).ok_or(
+                Error::InsufficientBalance(ERC20InsufficientBalance {
+                    sender: from,
+                    balance: current_supply,
+                    needed: value,
+                }),
+            )?;
+            self.total_supply.set(new_supply);
+        } else {
+            // Safely increase the `to` balance
+            let to_balance = self.balances.get(to);
+            let new_balance = to_balance.checked_add(value).ok_or(
+                Error::InsufficientBalance(ERC20InsufficientBalance {
+                    sender: to,
+                    balance: to_balance,
+                    needed: value,
+                }),
+            )?;
+            self.balances.setter(to).set(new_balance);
+        }
+
+        self._transfer_voting_units(from, to, value);
+
+        // Emit a Transfer event
+        evm::log(Transfer { from, to, value });
+
+        Ok(())
+    }
+
+    /// Burns `amount` tokens from `account` and sends it their ETH value at
+    /// the current rate, which must be at least `min_out`. See
+    /// [`Self::swap`] and [`Self::swap_min_out`] for the errors returned.
+    fn _swap(&mut self, account: Address, amount: U256, min_out: U256) -> Result<U256, Error> {
+        let payout = self.quote_swap(amount)?;
+        if payout.is_zero() {
+            return Err(Error::ZeroSwapAmount(ZeroSwapAmount { account }));
+        }
+        if payout < min_out {
+            return Err(Error::SlippageExceeded(SlippageExceeded {
+                amountOut: payout,
+                minAmountOut: min_out,
+            }));
+        }
+
+        let balance = self.spendable_balance_of(account);
+        if balance < amount {
+            return Err(Error::InsufficientSwapBalance(InsufficientSwapBalance {
+                account,
+                balance,
+                needed: amount,
+            }));
+        }
+
+        // ETH owed to queued tickets is not available to direct swaps
+        let reserved = self.quote_swap(self.queued_aton.get())?;
+        let available = contract::balance().saturating_sub(reserved);
+        if available < payout {
+            return Err(Error::InsufficientLiquidity(InsufficientLiquidity {
+                available,
+                needed: payout,
+            }));
+        }
+
+        self._burn(account, amount)?;
+        self._send_eth(account, payout)?;
+
+        self.eth_paid_out.set(self.eth_paid_out.get() + payout);
+        self._log_reserve();
+
+        Ok(payout)
+    }
+
+    /// Deactivates the lock of bet `bet_id` on behalf of the caller and
+    /// returns the player and the amount unlocked.
+    fn _unlock(&mut self, bet_id: U256) -> Result<(Address, U256), Error> {
+        let (player, amount) = self._check_unlock(bet_id)?;
+        self._clear_lock(bet_id, player, amount);
+
+        Ok((player, amount))
+    }
+
+    /// Ensures bet `bet_id` is locked and the caller may unlock it, and
+    /// returns the player and the locked amount.
+    fn _check_unlock(&self, bet_id: U256) -> Result<(Address, U256), Error> {
+        let bet = self.bet_locks.get(bet_id);
+        let (engine, player, amount) = (bet.engine.get(), bet.player.get(), bet.amount.get());
+        if !bet.active.get() {
+            return Err(Error::BetNotLocked(BetNotLocked { betId: bet_id }));
+        }
+        let caller = self._msg_sender();
+        if caller != engine {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
+                account: caller,
+            }));
+        }
+        // A revoked engine loses control over the locks it created
+        self._check_role(ENGINE_ROLE, caller)?;
+
+        Ok((player, amount))
+    }
+
+    /// Deactivates the lock of bet `bet_id`, freeing `amount` of `player`'s
+    /// balance.
+    fn _clear_lock(&mut self, bet_id: U256, player: Address, amount: U256) {
+        self.bet_locks.setter(bet_id).active.set(false);
+        let locked = self.locked_balances.get(player);
+        self.locked_balances.setter(player).set(locked - amount);
+    }
+
+    /// Processes the redemption queue. See [`Self::fulfill_swaps`].
+    fn _fulfill_swaps(&mut self, max: U256) -> Result<U256, Error> {
+        let count = self.swap_ticket_count.get();
+        let mut head = self.swap_queue_head.get();
+        let mut examined = U256::ZERO;
+        let mut fulfilled = U256::ZERO;
+
+        while examined < max && head < count {
+            let id = head + U256::from(1);
+            let ticket = self.swap_tickets.get(id);
+            let (owner, amount) = (ticket.owner.get(), ticket.amount.get());
+            let status = ticket.status.get().to::<u8>();
+            if status == TICKET_PENDING {
+                let payout = self.quote_swap(amount)?;
+                if contract::balance() < payout {
+                    break;
+                }
+
+                if payout.is_zero() || self._send_eth(owner, payout).is_err() {
+                    self._refund_swap_ticket(id)?;
+                } else {
+                    self.queued_aton.set(self.queued_aton.get() - amount);
+                    self._burn(contract::address(), amount)?;
+                    self.swap_tickets
+                        .setter(id)
+                        .status
+                        .set(U8::from(TICKET_FULFILLED));
+
+                    self.eth_paid_out.set(self.eth_paid_out.get() + payout);
+                    self._log_reserve();
+                    evm::log(SwapFulfilled { id, owner, amount, payout });
+                    fulfilled += U256::from(1);
+                }
+            }
+
+            head = id;
+            examined += U256::from(1);
+        }
+
+        self.swap_queue_head.set(head);
+        Ok(fulfilled)
+    }
+
+    /// Cancels pending ticket `id` and returns its ATON to the owner.
+    fn _refund_swap_ticket(&mut self, id: U256) -> Result<(), Error> {
+        let ticket = self.swap_tickets.get(id);
+        let (owner, amount) = (ticket.owner.get(), ticket.amount.get());
+
+        self.queued_aton.set(self.queued_aton.get() - amount);
+        self.swap_tickets.setter(id).status.set(U8::from(TICKET_CANCELED));
+        self._transfer(contract::address(), owner, amount)?;
+
+        evm::log(SwapCanceled { id, owner, amount });
+        Ok(())
+    }
+
+    /// Lends `amount` ATON to `receiver`. See [`Self::flash_loan`].
+    fn _flash_loan(
+        &mut self,
+        receiver: Address,
+        token: Address,
+        amount: U256,
+        data: Bytes,
+    ) -> Result<(), Error> {
+        let max_loan = self.max_flash_loan(token);
+        if amount > max_loan {
+            return Err(Error::ExceededMaxLoan(ERC3156ExceededMaxLoan { maxLoan: max_loan }));
+        }
+        let fee = self.flash_fee(token, amount)?;
+        let repayment = amount.checked_add(fee).ok_or(Error::ExceededMaxLoan(
+            ERC3156ExceededMaxLoan { maxLoan: max_loan },
+        ))?;
+
+        self._mint(receiver, amount)?;
+
+        let initiator = self._msg_sender();
+        let response = calls::on_flash_loan(self, receiver, initiator, token, amount, fee, data);
+        if response.ok() != Some(FLASH_LOAN_CALLBACK_SUCCESS) {
+            return Err(Error::InvalidFlashReceiver(ERC3156InvalidReceiver { receiver }));
+        }
+
+        self._spend_allowance(receiver, contract::address(), repayment)?;
+
+        let vault_address = self.vault_address.get();
+        if fee.is_zero() || vault_address.is_zero() {
+            self._burn(receiver, repayment)?;
+        } else {
+            self._burn(receiver, amount)?;
+            self._transfer(receiver, vault_address, fee)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends `amount` wei of excess ETH to `to`. See [`Self::sweep_excess`]
+    /// for the errors returned.
+    fn _sweep_excess(&mut self, to: Address, amount: U256) -> Result<(), Error> {
+        let excess = self.excess_eth()?;
+        if amount > excess {
+            return Err(Error::InsufficientExcess(InsufficientExcess {
+                excess,
+                needed: amount,
+            }));
+        }
+
+        self._send_eth(to, amount)?;
+
+        self.eth_swept.set(self.eth_swept.get() + amount);
+        self._log_reserve();
+
+        Ok(())
+    }
+
+    /// Emits a [`ReserveUpdated`] event with the current reserve totals.
+    fn _log_reserve(&self) {
+        evm::log(ReserveUpdated {
+            totalDeposited: self.eth_deposited.get(),
+            totalPaidOut: self.eth_paid_out.get(),
+            totalSwept: self.eth_swept.get(),
+            balance: contract::balance(),
+        });
+    }
+
+    /// Mints the ATON bought by `value` wei at the current rate to `engine`,
+    /// which must be at least `min_out`, and returns the amount minted.
+    fn _mint_for_eth(&mut self, engine: Address, value: U256, min_out: U256) -> Result<U256, Error> {
+        let amount = self.quote_mint(value)?;
+        if amount < min_out {
+            return Err(Error::SlippageExceeded(SlippageExceeded {
+                amountOut: amount,
+                minAmountOut: min_out,
+            }));
+        }
+
+        self._engine_mint(engine, amount)?;
+
+        self.eth_deposited.set(self.eth_deposited.get() + value);
+        self._log_reserve();
+
+        Ok(amount)
+    }
+
+    /// Reads the latest answer of `feed` and its decimals, rejecting answers
+    /// that are not positive, were never updated, or are older than
+    /// `max_age` seconds.
+    fn _fresh_price(&self, feed: Address, max_age: U256) -> Result<(I256, u8), Error> {
+        let (answer, updated_at, decimals) = self._latest_price(feed)?;
+        if !answer.is_positive() {
+            return Err(Error::InvalidPrice(InvalidPrice { answer }));
+        }
+        let now = U256::from(block::timestamp());
+        if updated_at.is_zero() || now > updated_at.saturating_add(max_age) {
+            return Err(Error::StalePrice(StalePrice {
+                updatedAt: updated_at,
+                maxAge: max_age,
+            }));
+        }
+
+        Ok((answer, decimals))
+    }
+
+    /// Reads the latest answer of `feed`, with its update time and decimals.
+    ///
+    /// # Errors
+    ///
+    /// If either call to `feed` fails, then the error
+    /// [`Error::PriceFeedCallFailed`] is returned.
+    fn _latest_price(&self, feed: Address) -> Result<(I256, U256, u8), Error> {
+        calls::latest_price(self, feed)
+            .map_err(|_| Error::PriceFeedCallFailed(PriceFeedCallFailed { feed }))
+    }
+
+    /// Sends `amount` wei to `to`.
+    ///
+    /// # Errors
+    ///
+    /// If the transfer fails, then the error [`Error::EthTransferFailed`] is
+    /// returned.
+    fn _send_eth(&mut self, to: Address, amount: U256) -> Result<(), Error> {
+        calls::transfer_eth(self, to, amount)
+            .map_err(|_| Error::EthTransferFailed(EthTransferFailed { to, amount }))
+    }
+
+    /// Mints `value` tokens to `engine`, consuming its mint quota.
+    ///
+    /// # Errors
+    ///
+    /// * If `engine` does not hold `ENGINE_ROLE`, then the error
+    ///   [`Error::AccessControlUnauthorizedAccount`] is returned.
+    /// * If `value` exceeds the remaining quota of `engine`, then the error
+    ///   [`Error::MintQuotaExceeded`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`Transfer`] event.
+    fn _engine_mint(&mut self, engine: Address, value: U256) -> Result<(), Error> {
+        self._require_not_paused(PAUSE_MINT)?;
+        self._check_role(ENGINE_ROLE, engine)?;
+
+        let remaining = self.mint_quota.get(engine);
+        if remaining < value {
+            return Err(Error::MintQuotaExceeded(MintQuotaExceeded {
+                engine,
+                remaining,
+                needed: value,
+            }));
+        }
+        self.mint_quota.setter(engine).set(remaining - value);
+
+        self._mint(engine, value)
+    }
+
+    /// Commission earned by `account` since its last checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// If the commission overflows, then the error
+    /// [`Error::CommissionOverflow`] is returned.
+    fn _unsettled_commission(&self, account: Address) -> Result<U256, Error> {
+        let acc_per_token = self.acc_commission_per_token.get();
+        let debt = self.commission_debt.get(account);
+        let earned = acc_per_token
+            .checked_sub(debt)
+            .and_then(|delta| self.balances.get(account).checked_mul(delta))
+            .ok_or(Error::CommissionOverflow(CommissionOverflow { account }))?;
+        Ok(earned / COMMISSION_PRECISION)
+    }
+
+    /// Moves the commission earned by `account` into its claimable credit and
+    /// checkpoints it at the current accumulator value.
+    ///
+    /// The zero address and the contract itself never accrue commission.
+    ///
+    /// # Errors
+    ///
+    /// If the commission overflows, then the error
+    /// [`Error::CommissionOverflow`] is returned.
+    fn _checkpoint_commission(&mut self, account: Address) -> Result<(), Error> {
+        if account.is_zero() || account == contract::address() {
+            return Ok(());
+        }
+
+        let earned = self._unsettled_commission(account)?;
+        if !earned.is_zero() {
+            let credit = self.commission_credit.get(account);
+            let credit = credit
+                .checked_add(earned)
+                .ok_or(Error::CommissionOverflow(CommissionOverflow { account }))?;
+            self.commission_credit.setter(account).set(credit);
+        }
+        self.commission_debt
+            .setter(account)
+            .set(self.acc_commission_per_token.get());
+
+        Ok(())
+    }
+
+    /// Returns the account a call is attributed to: the sender appended by
+    /// the trusted forwarder for relayed calls, otherwise `msg::sender()`.
+    pub fn _msg_sender(&self) -> Address {
+        let sender = msg::sender();
+        if self.is_trusted_forwarder(sender) {
+            if let Some(relayed) = RELAYED_SENDER.get() {
+                return relayed;
+            }
+        }
+        sender
+    }
+
+    // Ownable
+    /// Ensures the caller is the owner. Other holders of
+    /// `DEFAULT_ADMIN_ROLE` do not pass this check.
+    pub fn _only_owner(&self) -> Result<(), Error> {
+        let account = self._msg_sender();
+        if self.owner.get() != account {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
+                account,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Moves ownership, and with it `DEFAULT_ADMIN_ROLE`, to `new_owner`.
+    pub fn _transfer_ownership(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.initialized.set(true);
+        self.pending_owner.set(Address::ZERO);
+        self.owner.set(new_owner);
+        self._revoke_role(DEFAULT_ADMIN_ROLE, previous_owner);
+        if !new_owner.is_zero() {
+            self._grant_role(DEFAULT_ADMIN_ROLE, new_owner);
+        }
+        evm::log(OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        });
+    }
+
+    // ERC-1363
+    /// Calls `onTransferReceived` on `to` and checks that it accepted the
+    /// transfer.
+    ///
+    /// The receiver may call back into the token from the hook.
+    ///
+    /// # Errors
+    ///
+    /// If `to` reverts or returns anything but the `onTransferReceived`
+    /// selector, then the error [`Error::InvalidCallReceiver`] is returned.
+    fn _check_on_transfer_received(
+        &mut self,
+        operator: Address,
+        from: Address,
+        to: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<(), Error> {
+        let response = calls::on_transfer_received(self, to, operator, from, value, data);
+        if response.ok() != Some(ON_TRANSFER_RECEIVED) {
+            return Err(Error::InvalidCallReceiver(ERC1363InvalidReceiver { receiver: to }));
+        }
+
+        Ok(())
+    }
+
+    /// Calls `onApprovalReceived` on `spender` and checks that it accepted
+    /// the approval.
+    ///
+    /// # Errors
+    ///
+    /// If `spender` reverts or returns anything but the `onApprovalReceived`
+    /// selector, then the error [`Error::InvalidCallSpender`] is returned.
+    fn _check_on_approval_received(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<(), Error> {
+        let response = calls::on_approval_received(self, spender, owner, value, data);
+        if response.ok() != Some(ON_APPROVAL_RECEIVED) {
+            return Err(Error::InvalidCallSpender(ERC1363InvalidSpender { spender }));
+        }
+
+        Ok(())
+    }
+
+    // ReentrancyGuard
+    /// Marks the contract as entered.
+    ///
+    /// # Errors
+    ///
+    /// If the contract is already entered, then the error
+    /// [`Error::ReentrantCall`] is returned.
+    pub fn _non_reentrant_enter(&mut self) -> Result<(), Error> {
+        if self.entered.get() {
+            return Err(Error::ReentrantCall(ReentrancyGuardReentrantCall {}));
+        }
+        self.entered.set(true);
+
+        Ok(())
+    }
+
+    /// Clears the entered mark set by [`Self::_non_reentrant_enter`].
+    pub fn _non_reentrant_exit(&mut self) {
+        self.entered.set(false);
+    }
+
+    // Pausable
+    /// Ensures none of the pause `flags` is active.
+    ///
+    /// # Errors
+    ///
+    /// If any of `flags` is active, then the error [`Error::EnforcedPause`]
+    /// is returned.
+    pub fn _require_not_paused(&self, flags: u8) -> Result<(), Error> {
+        let active = self.paused() & flags;
+        if active != 0 {
+            return Err(Error::EnforcedPause(EnforcedPause { flag: active }));
+        }
+
+        Ok(())
+    }
+
+    /// Rejects `flags` containing bits other than the `PAUSE_*` flags.
+    fn _check_pause_flags(&self, flags: u8) -> Result<(), Error> {
+        if flags & !PAUSE_ALL != 0 {
+            return Err(Error::InvalidPauseFlags(InvalidPauseFlags { flags }));
+        }
+
+        Ok(())
+    }
+
+    /// Ensures the caller holds `PAUSER_ROLE` and is not the owner, and
+    /// returns the caller.
+    fn _only_pauser(&self) -> Result<Address, Error> {
+        let account = self._msg_sender();
+        self._check_role(PAUSER_ROLE, account)?;
+        if account == self.owner.get() {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount { account }));
+        }
+
+        Ok(account)
+    }
+
+    // AccessControl
+    /// Ensures `account` holds `role`.
+    ///
+    /// # Errors
+    ///
+    /// If `account` is missing `role`, then the error
+    /// [`Error::AccessControlUnauthorizedAccount`] is returned.
+    pub fn _check_role(&self, role: B256, account: Address) -> Result<(), Error> {
+        if !self.has_role(role, account) {
+            return Err(Error::AccessControlUnauthorizedAccount(
+                AccessControlUnauthorizedAccount {
+                    account,
+                    neededRole: role,
+                },
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Grants `role` to `account` without access checks.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`RoleGranted`] event, and an [`EngineRoleGranted`] event for
+    /// `ENGINE_ROLE`, if `account` did not hold `role`.
+    pub fn _grant_role(&mut self, role: B256, account: Address) -> bool {
+        if self.has_role(role, account) {
+            return false;
+        }
+
+        let sender = self._msg_sender();
+        self.roles.setter(role).insert(account, true);
+        evm::log(RoleGranted {
+            role,
+            account,
+            sender,
+        });
+        if role == ENGINE_ROLE {
+            evm::log(EngineRoleGranted { account, sender });
+        }
+
+        true
+    }
+
+    /// Revokes `role` from `account` without access checks.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`RoleRevoked`] event, and an [`EngineRoleRevoked`] event for
+    /// `ENGINE_ROLE`, if `account` held `role`.
+    pub fn _revoke_role(&mut self, role: B256, account: Address) -> bool {
+        if !self.has_role(role, account) {
+            return false;
+        }
+
+        let sender = self._msg_sender();
+        self.roles.setter(role).insert(account, false);
+        evm::log(RoleRevoked {
+            role,
+            account,
+            sender,
+        });
+        if role == ENGINE_ROLE {
+            evm::log(EngineRoleRevoked { account, sender });
+        }
+
+        true
+    }
+
+    // Helper function to calculate commission
+    fn _player_commission(&mut self, vault: Address, account: Address) -> Result<U256, Error> {
+        calls::player_commission(self, vault, account)
+            .map_err(|_| Error::VaultCallFailed(VaultCallFailed { vault, account }))
+    }
+
+    // Helper function to perform a transfer, keeping the underlying error
+    pub fn _perform_transfer(
+        &mut self,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Error> {
+        self._transfer(from, to, amount)
+    }
+
+    // Helper function to clear commission in the vault
+    fn _clear_commission(&mut self, vault: Address, account: Address) -> Result<(), Error> {
+        calls::clear_commission(self, vault, account)
+            .map_err(|_| Error::VaultCallFailed(VaultCallFailed { vault, account }))
+    }
+
+    /// Clears the vault commissions of `to` and `from` (and of the owner when
+    /// the contract itself is a party) in the vault, then pays them.
+    ///
+    /// In lenient mode every vault failure is skipped, and a commission is
+    /// only paid once the vault has cleared it. In strict mode the
+    /// settlement fails closed instead. The vault calls run under the
+    /// reentrancy guard, so a vault cannot call back into `swap`, `mint_aton`
+    /// or another settlement.
+    ///
+    /// # Errors
+    ///
+    /// * If a guarded call is already in progress, then the error
+    ///   [`Error::ReentrantCall`] is returned.
+    /// * In strict mode, if a vault call fails, then the error
+    ///   [`Error::VaultCallFailed`] is returned.
+    /// * In strict mode, if the vault can spend less than the commissions
+    ///   owed, then the error [`Error::VaultUnderfunded`] is returned.
+    /// * If paying a cleared commission fails, then the error of the
+    ///   underlying transfer, such as [`Error::EnforcedPause`], is returned.
+    pub fn _pay_commissions(&mut self, to: Address, from: Address) -> Result<(), Error> {
+        self._pay_commissions_of(vec![to, from])
+    }
+
+    /// Settles the vault commissions of every account in `parties` once,
+    /// under the reentrancy guard.
+    fn _pay_commissions_of(&mut self, parties: Vec<Address>) -> Result<(), Error> {
+        if self.flash_loan_active.get() {
+            // A borrower's transfer settles under the guard its loan holds;
+            // the vault still cannot call back into another settlement
+            self.flash_loan_active.set(false);
+            let result = self._settle_vault_commissions(parties);
+            self.flash_loan_active.set(true);
+            return result;
+        }
+
+        self._non_reentrant_enter()?;
+        let result = self._settle_vault_commissions(parties);
+        self._non_reentrant_exit();
+        result
+    }
+
+    /// Transfers `amounts[i]` from `from` to `recipients[i]` for every `i`,
+    /// settling each account's vault commission once. Returns the total
+    /// amount transferred.
+    fn _batch_transfer(
+        &mut self,
+        from: Address,
+        recipients: &[Address],
+        amounts: &[U256],
+    ) -> Result<U256, Error> {
+        if recipients.len() != amounts.len() {
+            return Err(Error::BatchLengthMismatch(BatchLengthMismatch {
+                recipients: U256::from(recipients.len()),
+                amounts: U256::from(amounts.len()),
+            }));
+        }
+
+        let mut parties = Vec::with_capacity(recipients.len() + 1);
+        parties.push(from);
+        parties.extend_from_slice(recipients);
+        self._pay_commissions_of(parties)?;
+
+        let mut total = U256::ZERO;
+        for (&to, &amount) in recipients.iter().zip(amounts) {
+            self._transfer(from, to, amount)?;
+            total += amount;
+        }
+        Ok(total)
+    }
+
+    /// Settlement performed by [`Self::_pay_commissions_of`].
+    fn _settle_vault_commissions(&mut self, mut parties: Vec<Address>) -> Result<(), Error> {
+        let vault_address = self.vault_address.get();
+        // Without a vault, commissions accrue in-contract only
+        if vault_address.is_zero() {
+            return Ok(());
+        }
+
+        let strict = self.strict_settlement.get();
+
+        if parties.contains(&contract::address()) {
+            parties.push(self.owner.get());
+        }
+        // Settle each account once, even if it appears in several roles
+        let mut accounts = Vec::with_capacity(parties.len());
+        for account in parties {
+            if !accounts.contains(&account) {
+                accounts.push(account);
+            }
+        }
+
+        let mut commissions = Vec::with_capacity(accounts.len());
+        let mut needed = U256::ZERO;
+        for &account in &accounts {
+            let commission = match self._player_commission(vault_address, account) {
+                Ok(commission) => commission,
+                Err(err) if strict => return Err(err),
+                Err(_) => U256::ZERO,
+            };
+            needed += commission;
+            commissions.push(commission);
+        }
+
+        // ATON held by bet locks cannot pay commissions
+        let balance = self.spendable_balance_of(vault_address);
+        if strict && balance < needed {
+            return Err(Error::VaultUnderfunded(VaultUnderfunded {
+                vault: vault_address,
+                balance,
+                needed,
+            }));
+        }
+
+        for (account, commission) in accounts.into_iter().zip(commissions) {
+            if commission.is_zero() {
+                continue;
+            }
+
+            // The commission stays owed until the vault can pay it
+            if !strict && self.spendable_balance_of(vault_address) < commission {
+                continue;
+            }
+            // Clear before paying, so a commission is never paid while it
+            // stays claimable in the vault
+            match self._clear_commission(vault_address, account) {
+                Ok(()) => {}
+                Err(err) if strict => return Err(err),
+                Err(_) => continue,
+            }
+            // A failed payment reverts the whole call, clear included
+            self._perform_transfer(vault_address, account, commission)?;
+        }
+
+        Ok(())
+    }
+
+    /// Consumes the current permit nonce of `owner` and returns it.
+    fn _use_nonce(&mut self, owner: Address) -> U256 {
+        let mut nonce = self.nonces.setter(owner);
+        let current = nonce.get();
+        nonce.set(current + U256::from(1));
+        current
+    }
+
+    /// Computes the EIP-712 domain separator for this token.
+    ///
+    /// The separator is derived on every call so that it stays correct after a
+    /// chain fork changes the chain id.
+    fn _domain_separator(&self) -> B256 {
+        keccak(
+            (
+                keccak(EIP712_DOMAIN_TYPEHASH),
+                keccak(self.name().as_bytes()),
+                keccak(EIP712_VERSION.as_bytes()),
+                U256::from(block::chainid()),
+                contract::address(),
+            )
+                .abi_encode(),
+        )
+    }
+
+    /// Hashes `struct_hash` into an EIP-712 typed data digest for this token.
+    fn _hash_typed_data(&self, struct_hash: B256) -> B256 {
+        let mut preimage = Vec::with_capacity(66);
+        preimage.extend_from_slice(b"\x19\x01");
+        preimage.extend_from_slice(self._domain_separator().as_slice());
+        preimage.extend_from_slice(struct_hash.as_slice());
+        keccak(preimage)
+    }
+
+    /// Computes the digest that `owner` signs to authorize a permit.
+    fn _permit_digest(
+        &self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        nonce: U256,
+        deadline: U256,
+    ) -> B256 {
+        let struct_hash = keccak(
+            (keccak(PERMIT_TYPEHASH), owner, spender, value, nonce, deadline).abi_encode(),
+        );
+        self._hash_typed_data(struct_hash)
+    }
+
+    /// Computes the digest signed to delegate votes to `delegatee`.
+    fn _delegation_digest(&self, delegatee: Address, nonce: U256, expiry: U256) -> B256 {
+        let struct_hash =
+            keccak((keccak(DELEGATION_TYPEHASH), delegatee, nonce, expiry).abi_encode());
+        self._hash_typed_data(struct_hash)
+    }
+
+    /// Computes the digest that `from` signs to authorize an ERC-3009
+    /// transfer of the `typehash` kind.
+    #[allow(clippy::too_many_arguments)]
+    fn _authorization_digest(
+        &self,
+        typehash: &[u8],
+        from: Address,
+        to: Address,
+        value: U256,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: B256,
+    ) -> B256 {
+        let struct_hash = keccak(
+            (keccak(typehash), from, to, value, valid_after, valid_before, nonce).abi_encode(),
+        );
+        self._hash_typed_data(struct_hash)
+    }
+
+    /// Computes the digest that `authorizer` signs to cancel `nonce`.
+    fn _cancel_authorization_digest(&self, authorizer: Address, nonce: B256) -> B256 {
+        let struct_hash =
+            keccak((keccak(CANCEL_AUTHORIZATION_TYPEHASH), authorizer, nonce).abi_encode());
+        self._hash_typed_data(struct_hash)
+    }
+
+    /// Validates a signed ERC-3009 transfer authorization and marks its
+    /// `nonce` as used.
+    #[allow(clippy::too_many_arguments)]
+    fn _use_authorization(
+        &mut self,
+        authorizer: Address,
+        nonce: B256,
+        valid_after: U256,
+        valid_before: U256,
+        digest: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Error> {
+        let now = U256::from(block::timestamp());
+        if now <= valid_after {
+            return Err(Error::AuthorizationNotYetValid(AuthorizationNotYetValid {
+                validAfter: valid_after,
+            }));
+        }
+        if now >= valid_before {
+            return Err(Error::AuthorizationExpired(AuthorizationExpired {
+                validBefore: valid_before,
+            }));
+        }
+        self._require_unused_authorization(authorizer, nonce)?;
+        self._check_authorization_signer(authorizer, digest, v, r, s)?;
+
+        self.authorization_states.setter(authorizer).insert(nonce, true);
+        evm::log(AuthorizationUsed { authorizer, nonce });
+
+        Ok(())
+    }
+
+    /// Ensures the ERC-3009 `nonce` of `authorizer` is still unused.
+    fn _require_unused_authorization(&self, authorizer: Address, nonce: B256) -> Result<(), Error> {
+        if self.authorization_state(authorizer, nonce) {
+            return Err(Error::AuthorizationAlreadyUsed(AuthorizationAlreadyUsed {
+                authorizer,
+                nonce,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Ensures `digest` was signed by `authorizer`.
+    fn _check_authorization_signer(
+        &self,
+        authorizer: Address,
+        digest: B256,
+        v: u8,
+        r: B256,
+        s: B256,
+    ) -> Result<(), Error> {
+        let signer = self._recover(digest, v, r, s)?;
+        if signer.is_zero() || signer != authorizer {
+            return Err(Error::InvalidAuthorizationSigner(InvalidAuthorizationSigner {
+                signer,
+                authorizer,
+            }));
+        }
+
+        Ok(())
+    }
+
+    // ERC20Votes
+    /// Moves the voting power of `account` from its current delegate to
+    /// `delegatee`.
+    fn _delegate(&mut self, account: Address, delegatee: Address) {
+        let previous = self.delegates.get(account);
+        self.delegates.setter(account).set(delegatee);
+
+        evm::log(DelegateChanged {
+            delegator: account,
+            fromDelegate: previous,
+            toDelegate: delegatee,
+        });
+
+        let units = self.balances.get(account);
+        self._move_delegate_votes(previous, delegatee, units);
+    }
+
+    /// Records a balance change of `value` from `from` to `to` in the total
+    /// supply and delegate checkpoints.
+    fn _transfer_voting_units(&mut self, from: Address, to: Address, value: U256) {
+        if from.is_zero() {
+            let supply = self.total_supply.get();
+            Self::_push_checkpoint(&mut self.supply_checkpoints, supply);
+        }
+        if to.is_zero() {
+            let supply = self.total_supply.get();
+            Self::_push_checkpoint(&mut self.supply_checkpoints, supply);
+        }
+
+        let from_delegate = self.delegates.get(from);
+        let to_delegate = self.delegates.get(to);
+        self._move_delegate_votes(from_delegate, to_delegate, value);
+    }
+
+    /// Moves `amount` votes from delegate `from` to delegate `to`.
+    fn _move_delegate_votes(&mut self, from: Address, to: Address, amount: U256) {
+        if from == to || amount.is_zero() {
+            return;
+        }
+
+        if !from.is_zero() {
+            let mut checkpoints = self.vote_checkpoints.setter(from);
+            let previous = Self::_latest_checkpoint(&checkpoints);
+            let votes = previous - amount;
+            Self::_push_checkpoint(&mut checkpoints, votes);
+            evm::log(DelegateVotesChanged {
+                delegate: from,
+                previousVotes: previous,
+                newVotes: votes,
+            });
+        }
+        if !to.is_zero() {
+            let mut checkpoints = self.vote_checkpoints.setter(to);
+            let previous = Self::_latest_checkpoint(&checkpoints);
+            let votes = previous + amount;
+            Self::_push_checkpoint(&mut checkpoints, votes);
+            evm::log(DelegateVotesChanged {
+                delegate: to,
+                previousVotes: previous,
+                newVotes: votes,
+            });
+        }
+    }
+
+    // Vault management
+    /// Replaces the vault with `new_vault`.
+    fn _set_vault(&mut self, new_vault: Address) {
+        let old_vault = self.vault_address.get();
+        self.vault_address.set(new_vault);
+        evm::log(VaultUpdated {
+            oldVault: old_vault,
+            newVault: new_vault,
+        });
+    }
+
+    /// Discards the pending vault update.
+    fn _clear_pending_vault(&mut self) {
+        self.pending_vault.set(Address::ZERO);
+        self.vault_update_eta.set(U256::ZERO);
+    }
+
+    /// Discards the pending vault delay decrease, if any.
+    fn _clear_pending_vault_delay(&mut self) {
+        self.pending_vault_delay.set(U256::ZERO);
+        self.vault_delay_eta.set(U256::ZERO);
+    }
+
+    // Snapshots
+    /// Records the pre-change balances of `from` and `to`, and the
+    /// pre-change total supply on mints and burns, for the current snapshot.
+    fn _update_snapshots(&mut self, from: Address, to: Address) {
+        let id = self.current_snapshot_id.get();
+        if id.is_zero() {
+            return;
+        }
+
+        for account in [from, to] {
+            if !account.is_zero() {
+                let balance = self.balances.get(account);
+                Self::_write_snapshot(&mut self.account_snapshots.setter(account), id, balance);
+            }
+        }
+        if from.is_zero() || to.is_zero() {
+            let supply = self.total_supply.get();
+            Self::_write_snapshot(&mut self.supply_snapshots, id, supply);
+        }
+    }
+
+    /// Appends `value` for snapshot `id` unless a value was already written
+    /// since that snapshot was taken.
+    fn _write_snapshot(snapshots: &mut StorageVec<BalanceSnapshot>, id: U256, value: U256) {
+        let len = snapshots.len();
+        let last_id = match len {
+            0 => U256::ZERO,
+            len => snapshots.get(len - 1).map_or(U256::ZERO, |last| last.id.get()),
+        };
+        if last_id < id {
+            let mut snapshot = snapshots.grow();
+            snapshot.id.set(id);
+            snapshot.value.set(value);
+        }
+    }
+
+    /// Returns the value recorded at snapshot `id`, or `None` if it has not
+    /// changed since, in which case the current value applies.
+    fn _value_at(&self, snapshots: &StorageVec<BalanceSnapshot>, id: U256) -> Result<Option<U256>, Error> {
+        let current_id = self.current_snapshot_id.get();
+        if id.is_zero() || id > current_id {
+            return Err(Error::InvalidSnapshotId(ERC20SnapshotInvalidId {
+                id,
+                currentId: current_id,
+            }));
+        }
+
+        // First entry written after snapshot `id` was taken
+        let (mut low, mut high) = (0, snapshots.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let mid_id = snapshots.get(mid).map_or(U256::MAX, |snapshot| snapshot.id.get());
+            if mid_id < id {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(snapshots.get(low).map(|snapshot| snapshot.value.get()))
+    }
+
+    /// Ensures `timepoint` is a past block and returns it.
+    fn _past_timepoint(&self, timepoint: U256) -> Result<u64, Error> {
+        let clock = self.clock();
+        if timepoint >= U256::from(clock) {
+            return Err(Error::FutureLookup(ERC5805FutureLookup { timepoint, clock }));
+        }
+
+        Ok(timepoint.to::<u64>())
+    }
+
+    /// Returns the value of the most recent checkpoint, or zero if there is
+    /// none.
+    fn _latest_checkpoint(checkpoints: &StorageVec<Checkpoint>) -> U256 {
+        match checkpoints.len() {
+            0 => U256::ZERO,
+            len => checkpoints.get(len - 1).map_or(U256::ZERO, |last| last.votes.get()),
+        }
+    }
+
+    /// Returns the value in force at the end of `block`, found by binary
+    /// search over `checkpoints`.
+    fn _lookup_checkpoint(checkpoints: &StorageVec<Checkpoint>, block: u64) -> U256 {
+        let (mut low, mut high) = (0, checkpoints.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let from_block = checkpoints
+                .get(mid)
+                .map_or(u64::MAX, |checkpoint| checkpoint.from_block.get().to::<u64>());
+            if from_block > block {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        match high {
+            0 => U256::ZERO,
+            index => checkpoints.get(index - 1).map_or(U256::ZERO, |found| found.votes.get()),
+        }
+    }
+
+    /// Records `votes` as of the current block, overwriting the last
+    /// checkpoint if it was written in the same block.
+    fn _push_checkpoint(checkpoints: &mut StorageVec<Checkpoint>, votes: U256) {
+        let now = block::number();
+        let len = checkpoints.len();
+        if len > 0 {
+            if let Some(mut last) = checkpoints.setter(len - 1) {
+                if last.from_block.get().to::<u64>() == now {
+                    last.votes.set(votes);
+                    return;
+                }
+            }
+        }
+
+        let mut checkpoint = checkpoints.grow();
+        checkpoint.from_block.set(U64::from(now));
+        checkpoint.votes.set(votes);
+    }
+
+    /// Recovers the signer of `digest` through the `ecrecover` precompile.
+    ///
+    /// # Errors
+    ///
+    /// If `s` lies in the upper half of the curve order, then the error
+    /// [`Error::InvalidSignatureS`] is returned.
+    ///
+    /// Returns `Address::ZERO` if the precompile cannot recover a signer.
+    fn _recover(&self, digest: B256, v: u8, r: B256, s: B256) -> Result<Address, Error> {
+        if U256::from_be_bytes(s.0) > SECP256K1_HALF_ORDER {
+            return Err(Error::InvalidSignatureS(ECDSAInvalidSignatureS { s }));
+        }
+
+        Ok(calls::ecrecover(self, digest, v, r, s).unwrap_or(Address::ZERO))
     }
 }
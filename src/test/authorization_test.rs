@@ -0,0 +1,280 @@
+// tests/authorization_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, BOB};
+    use crate::{
+        Erc20Aton, Error, RECEIVE_WITH_AUTHORIZATION_TYPEHASH, TRANSFER_WITH_AUTHORIZATION_TYPEHASH,
+    };
+    use stylus_sdk::{
+        alloy_primitives::{B256, U256},
+        block, msg,
+    };
+
+    /// Test that authorizations outside their validity window are rejected.
+    #[motsu::test]
+    fn authorization_rejects_outside_window(contract: Erc20Aton) {
+        let from = msg::sender();
+        let to = BOB;
+        let now = U256::from(block::timestamp());
+        let nonce = B256::repeat_byte(7);
+
+        let result = contract.transfer_with_authorization(
+            from,
+            to,
+            U256::from(1),
+            now,
+            now + U256::from(3_600),
+            nonce,
+            27,
+            B256::repeat_byte(1),
+            B256::repeat_byte(1),
+        );
+        assert!(matches!(result, Err(Error::AuthorizationNotYetValid(_))));
+
+        let result = contract.transfer_with_authorization(
+            from,
+            to,
+            U256::from(1),
+            U256::ZERO,
+            now,
+            nonce,
+            27,
+            B256::repeat_byte(1),
+            B256::repeat_byte(1),
+        );
+        assert!(matches!(result, Err(Error::AuthorizationExpired(_))));
+        assert!(!contract.authorization_state(from, nonce));
+    }
+
+    /// Test that a used or canceled nonce cannot be reused.
+    #[motsu::test]
+    fn authorization_rejects_used_nonce(contract: Erc20Aton) {
+        let from = msg::sender();
+        let to = BOB;
+        let now = U256::from(block::timestamp());
+        let nonce = B256::repeat_byte(7);
+
+        // Mark the nonce as used, as a successful authorization would
+        contract.authorization_states.setter(from).insert(nonce, true);
+        assert!(contract.authorization_state(from, nonce));
+
+        let result = contract.transfer_with_authorization(
+            from,
+            to,
+            U256::from(1),
+            U256::ZERO,
+            now + U256::from(3_600),
+            nonce,
+            27,
+            B256::repeat_byte(1),
+            B256::repeat_byte(1),
+        );
+        assert!(matches!(result, Err(Error::AuthorizationAlreadyUsed(_))));
+
+        let result =
+            contract.cancel_authorization(from, nonce, 27, B256::repeat_byte(1), B256::repeat_byte(1));
+        assert!(matches!(result, Err(Error::AuthorizationAlreadyUsed(_))));
+    }
+
+    /// Test that an unsigned authorization moves no tokens and leaves the
+    /// nonce unused.
+    #[motsu::test]
+    fn authorization_rejects_invalid_signer(contract: Erc20Aton) {
+        let from = msg::sender();
+        let to = BOB;
+        let now = U256::from(block::timestamp());
+        let nonce = B256::repeat_byte(7);
+        assert!(contract._mint(from, U256::from(10)).is_ok());
+
+        let result = contract.transfer_with_authorization(
+            from,
+            to,
+            U256::from(10),
+            U256::ZERO,
+            now + U256::from(3_600),
+            nonce,
+            27,
+            B256::repeat_byte(1),
+            B256::repeat_byte(1),
+        );
+        assert!(matches!(result, Err(Error::InvalidAuthorizationSigner(_))));
+
+        let result =
+            contract.cancel_authorization(from, nonce, 27, B256::repeat_byte(1), B256::repeat_byte(1));
+        assert!(matches!(result, Err(Error::InvalidAuthorizationSigner(_))));
+
+        assert!(!contract.authorization_state(from, nonce));
+        assert_eq!(contract.balance_of(from), U256::from(10));
+        assert_eq!(contract.balance_of(to), U256::ZERO);
+    }
+
+    /// Test that a signed transfer authorization moves the tokens and burns
+    /// its nonce.
+    #[motsu::test]
+    fn transfer_with_authorization_moves_funds(contract: Erc20Aton) {
+        let from = helpers::signer();
+        let to = BOB;
+        let valid_before = U256::from(block::timestamp() + 3_600);
+        let nonce = B256::repeat_byte(7);
+        assert!(contract._mint(from, U256::from(10)).is_ok());
+
+        let digest = contract._authorization_digest(
+            TRANSFER_WITH_AUTHORIZATION_TYPEHASH,
+            from,
+            to,
+            U256::from(6),
+            U256::ZERO,
+            valid_before,
+            nonce,
+        );
+        let (v, r, s) = helpers::sign(digest);
+        let result = contract.transfer_with_authorization(
+            from,
+            to,
+            U256::from(6),
+            U256::ZERO,
+            valid_before,
+            nonce,
+            v,
+            r,
+            s,
+        );
+        assert!(result.is_ok());
+        assert_eq!(contract.balance_of(from), U256::from(4));
+        assert_eq!(contract.balance_of(to), U256::from(6));
+        assert!(contract.authorization_state(from, nonce));
+
+        let result = contract.transfer_with_authorization(
+            from,
+            to,
+            U256::from(6),
+            U256::ZERO,
+            valid_before,
+            nonce,
+            v,
+            r,
+            s,
+        );
+        assert!(matches!(result, Err(Error::AuthorizationAlreadyUsed(_))));
+        assert_eq!(contract.balance_of(from), U256::from(4));
+    }
+
+    /// Test that the payee can redeem a signed receive authorization.
+    #[motsu::test]
+    fn receive_with_authorization_moves_funds(contract: Erc20Aton) {
+        let from = helpers::signer();
+        let to = msg::sender();
+        let valid_before = U256::from(block::timestamp() + 3_600);
+        let nonce = B256::repeat_byte(7);
+        assert!(contract._mint(from, U256::from(10)).is_ok());
+
+        let digest = contract._authorization_digest(
+            RECEIVE_WITH_AUTHORIZATION_TYPEHASH,
+            from,
+            to,
+            U256::from(10),
+            U256::ZERO,
+            valid_before,
+            nonce,
+        );
+        let (v, r, s) = helpers::sign(digest);
+        let result = contract.receive_with_authorization(
+            from,
+            to,
+            U256::from(10),
+            U256::ZERO,
+            valid_before,
+            nonce,
+            v,
+            r,
+            s,
+        );
+        assert!(result.is_ok());
+        assert_eq!(contract.balance_of(from), U256::ZERO);
+        assert_eq!(contract.balance_of(to), U256::from(10));
+        assert!(contract.authorization_state(from, nonce));
+    }
+
+    /// Test that a signed cancellation burns the nonce before it is used.
+    #[motsu::test]
+    fn cancel_authorization_burns_nonce(contract: Erc20Aton) {
+        let from = helpers::signer();
+        let to = BOB;
+        let valid_before = U256::from(block::timestamp() + 3_600);
+        let nonce = B256::repeat_byte(7);
+        assert!(contract._mint(from, U256::from(10)).is_ok());
+
+        let (v, r, s) = helpers::sign(contract._cancel_authorization_digest(from, nonce));
+        assert!(contract.cancel_authorization(from, nonce, v, r, s).is_ok());
+        assert!(contract.authorization_state(from, nonce));
+
+        let digest = contract._authorization_digest(
+            TRANSFER_WITH_AUTHORIZATION_TYPEHASH,
+            from,
+            to,
+            U256::from(10),
+            U256::ZERO,
+            valid_before,
+            nonce,
+        );
+        let (v, r, s) = helpers::sign(digest);
+        let result = contract.transfer_with_authorization(
+            from,
+            to,
+            U256::from(10),
+            U256::ZERO,
+            valid_before,
+            nonce,
+            v,
+            r,
+            s,
+        );
+        assert!(matches!(result, Err(Error::AuthorizationAlreadyUsed(_))));
+        assert_eq!(contract.balance_of(from), U256::from(10));
+    }
+
+    /// Test that only the payee can submit a receive authorization, and that
+    /// it cannot be replayed as a transfer authorization.
+    #[motsu::test]
+    fn receive_with_authorization_requires_payee(contract: Erc20Aton) {
+        let from = BOB;
+        let to = ALICE;
+        let now = U256::from(block::timestamp());
+        let valid_before = now + U256::from(3_600);
+        let nonce = B256::repeat_byte(7);
+
+        let result = contract.receive_with_authorization(
+            from,
+            to,
+            U256::from(1),
+            U256::ZERO,
+            valid_before,
+            nonce,
+            27,
+            B256::repeat_byte(1),
+            B256::repeat_byte(1),
+        );
+        assert!(matches!(result, Err(Error::InvalidAuthorizationCaller(_))));
+
+        let transfer = contract._authorization_digest(
+            TRANSFER_WITH_AUTHORIZATION_TYPEHASH,
+            from,
+            to,
+            U256::from(1),
+            U256::ZERO,
+            valid_before,
+            nonce,
+        );
+        let receive = contract._authorization_digest(
+            RECEIVE_WITH_AUTHORIZATION_TYPEHASH,
+            from,
+            to,
+            U256::from(1),
+            U256::ZERO,
+            valid_before,
+            nonce,
+        );
+        assert_ne!(transfer, receive);
+    }
+}
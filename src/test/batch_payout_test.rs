@@ -0,0 +1,100 @@
+// tests/batch_payout_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, BOB, VAULT};
+    use crate::test::mock_vault;
+    use crate::{Erc20Aton, Error};
+    use stylus_sdk::{
+        alloy_primitives::{Address, U256},
+        msg,
+    };
+
+    /// Initializes the token with a funded vault and makes `msg::sender()` a
+    /// stake engine holding `engine_balance` ATON.
+    fn setup(contract: &mut Erc20Aton, engine_balance: u64) {
+        assert!(helpers::initialize_with_vault(contract, 1_000));
+        assert!(contract.update_stake_engine(msg::sender(), true).is_ok());
+        assert!(contract._mint(msg::sender(), U256::from(engine_balance)).is_ok());
+    }
+
+    /// Returns `count` distinct recipient addresses.
+    fn recipients(count: usize) -> Vec<Address> {
+        (1..=count as u64)
+            .map(|i| Address::left_padding_from(&i.to_be_bytes()))
+            .collect()
+    }
+
+    /// Test that a batch pays every recipient and settles each account once.
+    #[motsu::test]
+    fn batch_payout_pays_recipients(contract: Erc20Aton) {
+        setup(contract, 100);
+        let engine = msg::sender();
+        mock_vault::set_commission(engine, U256::from(2));
+        mock_vault::set_commission(ALICE, U256::from(3));
+
+        let result = contract.batch_payout(
+            vec![ALICE, BOB, ALICE],
+            vec![U256::from(10), U256::from(20), U256::from(5)],
+        );
+        assert!(result.ok().unwrap());
+
+        assert_eq!(contract.balance_of(ALICE), U256::from(18));
+        assert_eq!(contract.balance_of(BOB), U256::from(20));
+        assert_eq!(contract.balance_of(engine), U256::from(67));
+        assert_eq!(contract.balance_of(VAULT), U256::from(995));
+        assert_eq!(mock_vault::queries(), 3);
+        assert_eq!(mock_vault::cleared(), vec![engine, ALICE]);
+    }
+
+    /// Test that only stake engines can pay out in batches.
+    #[motsu::test]
+    fn batch_payout_requires_engine(contract: Erc20Aton) {
+        setup(contract, 100);
+        assert!(contract.update_stake_engine(msg::sender(), false).is_ok());
+
+        let result = contract.batch_payout(vec![ALICE], vec![U256::from(1)]);
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+    }
+
+    /// Test that mismatched arrays and overdrawn batches revert as a whole.
+    #[motsu::test]
+    fn batch_payout_rejects_invalid_batches(contract: Erc20Aton) {
+        setup(contract, 100);
+
+        let result = contract.batch_payout(vec![ALICE, BOB], vec![U256::from(1)]);
+        assert!(matches!(result, Err(Error::BatchLengthMismatch(_))));
+
+        let result =
+            contract.batch_payout(vec![ALICE, BOB], vec![U256::from(60), U256::from(60)]);
+        assert!(matches!(result, Err(Error::InsufficientBalance(_))));
+    }
+
+    /// Test that a batch payout makes fewer vault calls than individual
+    /// transfers.
+    ///
+    /// This counts vault round-trips only. motsu has no gas metering, so this
+    /// is not a gas-per-recipient benchmark.
+    #[motsu::test]
+    fn batch_payout_vault_calls(contract: Erc20Aton) {
+        const RECIPIENTS: usize = 256;
+        setup(contract, 1_000_000);
+        // Every recipient appears twice in the batch
+        let unique = recipients(RECIPIENTS / 2);
+        let batch: Vec<Address> = unique.iter().chain(&unique).copied().collect();
+        let amounts = vec![U256::from(10); RECIPIENTS];
+
+        assert!(contract.batch_payout(batch, amounts).is_ok());
+        let batch_queries = mock_vault::queries();
+
+        for &to in &unique {
+            assert_eq!(contract.balance_of(to), U256::from(20));
+            assert!(contract.transfer(to, U256::from(10)).is_ok());
+        }
+        let single_queries = mock_vault::queries() - batch_queries;
+
+        // One settlement for the engine plus one per unique recipient
+        assert_eq!(batch_queries, RECIPIENTS / 2 + 1);
+        assert_eq!(single_queries, 2 * RECIPIENTS / 2);
+    }
+}
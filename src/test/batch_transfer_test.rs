@@ -0,0 +1,106 @@
+// tests/batch_transfer_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, BOB};
+    use crate::{Erc20Aton, Error};
+    use alloy_sol_types::{sol, SolCall};
+    use stylus_sdk::{
+        abi::Bytes,
+        alloy_primitives::U256,
+        msg,
+    };
+
+    sol! {
+        function approve(address spender, uint256 value) external returns (bool);
+        function transfer(address to, uint256 value) external returns (bool);
+        function balanceOf(address account) external view returns (uint256);
+    }
+
+    /// Initializes the token and gives `msg::sender()` 100 ATON.
+    fn setup(contract: &mut Erc20Aton) {
+        assert!(helpers::initialize_with_balance(contract, msg::sender(), 100));
+    }
+
+    /// Test that a batch transfer pays every recipient from the caller.
+    #[motsu::test]
+    fn batch_transfer_pays_recipients(contract: Erc20Aton) {
+        setup(contract);
+
+        let result = contract.batch_transfer(
+            vec![ALICE, BOB, ALICE],
+            vec![U256::from(10), U256::from(20), U256::from(5)],
+        );
+        assert!(result.ok().unwrap());
+        assert_eq!(contract.balance_of(ALICE), U256::from(15));
+        assert_eq!(contract.balance_of(BOB), U256::from(20));
+        assert_eq!(contract.balance_of(msg::sender()), U256::from(65));
+    }
+
+    /// Test that batch transfers reject invalid batches as a whole.
+    #[motsu::test]
+    fn batch_transfer_rejects_invalid_batches(contract: Erc20Aton) {
+        setup(contract);
+
+        let result = contract.batch_transfer(vec![ALICE], vec![U256::from(1), U256::from(2)]);
+        assert!(matches!(result, Err(Error::BatchLengthMismatch(_))));
+
+        let result =
+            contract.batch_transfer(vec![ALICE, BOB], vec![U256::from(60), U256::from(60)]);
+        assert!(matches!(result, Err(Error::InsufficientBalance(_))));
+    }
+
+    /// Test that a multicall runs each call as the caller and returns the
+    /// results in order.
+    #[motsu::test]
+    fn multicall_dispatches_calls(contract: Erc20Aton) {
+        setup(contract);
+
+        let calls = vec![
+            Bytes(approveCall { spender: BOB, value: U256::from(5) }.abi_encode()),
+            Bytes(transferCall { to: ALICE, value: U256::from(10) }.abi_encode()),
+            Bytes(balanceOfCall { account: ALICE }.abi_encode()),
+        ];
+        let results = contract.multicall(calls).ok().unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert!(approveCall::abi_decode_returns(&results[0], true).unwrap()._0);
+        assert!(transferCall::abi_decode_returns(&results[1], true).unwrap()._0);
+        let balance = balanceOfCall::abi_decode_returns(&results[2], true).unwrap()._0;
+        assert_eq!(balance, U256::from(10));
+        assert_eq!(contract.allowance(msg::sender(), BOB), U256::from(5));
+        assert_eq!(contract.balance_of(msg::sender()), U256::from(90));
+    }
+
+    /// Test that a multicall fails on the first failing call and reports its
+    /// index and revert data.
+    #[motsu::test]
+    fn multicall_fails_on_first_error(contract: Erc20Aton) {
+        setup(contract);
+
+        let calls = vec![
+            Bytes(transferCall { to: ALICE, value: U256::from(10) }.abi_encode()),
+            Bytes(transferCall { to: ALICE, value: U256::from(1_000) }.abi_encode()),
+            Bytes(transferCall { to: BOB, value: U256::from(10) }.abi_encode()),
+        ];
+        match contract.multicall(calls) {
+            Err(Error::MulticallFailed(failure)) => {
+                assert_eq!(failure.index, U256::from(1));
+                assert!(!failure.reason.is_empty());
+            }
+            _ => panic!("expected MulticallFailed"),
+        }
+        assert_eq!(contract.balance_of(BOB), U256::ZERO);
+
+        // Calldata without a known selector fails with empty revert data
+        for call in [vec![0x12, 0x34], vec![0xde, 0xad, 0xbe, 0xef]] {
+            match contract.multicall(vec![Bytes(call)]) {
+                Err(Error::MulticallFailed(failure)) => {
+                    assert_eq!(failure.index, U256::ZERO);
+                    assert!(failure.reason.is_empty());
+                }
+                _ => panic!("expected MulticallFailed"),
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+// tests/bet_lock_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, BOB};
+    use crate::{Erc20Aton, Error, DEFAULT_ADMIN_ROLE, ENGINE_ROLE};
+    use stylus_sdk::{
+        alloy_primitives::U256,
+        msg,
+    };
+
+    /// Initializes the token, makes `msg::sender()` a stake engine and gives
+    /// `ALICE` 100 ATON, all of it approved to the engine.
+    fn setup(contract: &mut Erc20Aton) {
+        assert!(helpers::initialize_with_balance(contract, ALICE, 100));
+        assert!(contract.update_stake_engine(msg::sender(), true).is_ok());
+        assert!(contract._approve(ALICE, msg::sender(), U256::from(100), true).is_ok());
+    }
+
+    /// Test that locked ATON stays in the balance but cannot be spent.
+    #[motsu::test]
+    fn lock_excludes_balance_from_spending(contract: Erc20Aton) {
+        setup(contract);
+        let bet = U256::from(7);
+
+        assert!(contract.lock(ALICE, U256::from(60), bet).is_ok());
+        assert_eq!(contract.balance_of(ALICE), U256::from(100));
+        assert_eq!(contract.locked_balance_of(ALICE), U256::from(60));
+        assert_eq!(contract.spendable_balance_of(ALICE), U256::from(40));
+        assert_eq!(contract.bet_lock(bet), (msg::sender(), ALICE, U256::from(60), true));
+        assert_eq!(contract.allowance(ALICE, msg::sender()), U256::from(40));
+
+        let result = contract._transfer(ALICE, BOB, U256::from(41));
+        assert!(matches!(result, Err(Error::InsufficientBalance(_))));
+        let result = contract._burn(ALICE, U256::from(41));
+        assert!(matches!(result, Err(Error::InsufficientBalance(_))));
+        assert!(contract._transfer(ALICE, BOB, U256::from(40)).is_ok());
+
+        // The locked amount cannot be locked twice
+        let result = contract.lock(ALICE, U256::from(1), U256::from(8));
+        assert!(matches!(result, Err(Error::InsufficientBalance(_))));
+    }
+
+    /// Test that releasing a lock makes the ATON spendable again.
+    #[motsu::test]
+    fn release_unlocks_balance(contract: Erc20Aton) {
+        setup(contract);
+        let bet = U256::from(7);
+        assert!(contract.lock(ALICE, U256::from(60), bet).is_ok());
+
+        assert!(contract.release(bet).is_ok());
+        assert_eq!(contract.locked_balance_of(ALICE), U256::ZERO);
+        assert_eq!(contract.spendable_balance_of(ALICE), U256::from(100));
+        assert!(!contract.bet_lock(bet).3);
+
+        let result = contract.release(bet);
+        assert!(matches!(result, Err(Error::BetNotLocked(_))));
+        // A bet id is never reused
+        let result = contract.lock(ALICE, U256::from(10), bet);
+        assert!(matches!(result, Err(Error::BetAlreadyLocked(_))));
+    }
+
+    /// Test that settling pays the winner out of the lock and frees the rest.
+    #[motsu::test]
+    fn settle_pays_winner(contract: Erc20Aton) {
+        setup(contract);
+        let bet = U256::from(7);
+        assert!(contract.lock(ALICE, U256::from(60), bet).is_ok());
+
+        let result = contract.settle(bet, BOB, U256::from(61));
+        assert!(matches!(result, Err(Error::PayoutExceedsLock(_))));
+        // Unknown bets are reported as such, whatever the payout
+        let result = contract.settle(U256::from(8), BOB, U256::from(61));
+        assert!(matches!(result, Err(Error::BetNotLocked(_))));
+
+        assert!(contract.settle(bet, BOB, U256::from(45)).is_ok());
+        assert_eq!(contract.balance_of(ALICE), U256::from(55));
+        assert_eq!(contract.balance_of(BOB), U256::from(45));
+        assert_eq!(contract.locked_balance_of(ALICE), U256::ZERO);
+
+        let result = contract.settle(bet, BOB, U256::ZERO);
+        assert!(matches!(result, Err(Error::BetNotLocked(_))));
+    }
+
+    /// Test that an engine can only lock what the player approved to it.
+    #[motsu::test]
+    fn lock_requires_player_allowance(contract: Erc20Aton) {
+        setup(contract);
+        assert!(contract._approve(ALICE, msg::sender(), U256::from(50), true).is_ok());
+
+        let result = contract.lock(ALICE, U256::from(60), U256::from(7));
+        assert!(matches!(result, Err(Error::InsufficientAllowance(_))));
+        assert_eq!(contract.locked_balance_of(ALICE), U256::ZERO);
+
+        assert!(contract.lock(ALICE, U256::from(50), U256::from(7)).is_ok());
+        let result = contract.lock(ALICE, U256::from(1), U256::from(8));
+        assert!(matches!(result, Err(Error::InsufficientAllowance(_))));
+        assert_eq!(contract.locked_balance_of(ALICE), U256::from(50));
+    }
+
+    /// Test that only stake engines lock, and only the locking engine
+    /// releases or settles.
+    #[motsu::test]
+    fn locks_are_engine_controlled(contract: Erc20Aton) {
+        setup(contract);
+        let bet = U256::from(7);
+
+        let result = contract.lock(ALICE, U256::ZERO, bet);
+        assert!(matches!(result, Err(Error::InvalidLockAmount(_))));
+        assert!(contract.lock(ALICE, U256::from(60), bet).is_ok());
+
+        // Another engine created the lock
+        contract.bet_locks.setter(bet).engine.set(BOB);
+        let result = contract.release(bet);
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+        let result = contract.settle(bet, BOB, U256::from(1));
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+
+        // A revoked engine can no longer lock, release or settle
+        contract.bet_locks.setter(bet).engine.set(msg::sender());
+        contract._revoke_role(ENGINE_ROLE, msg::sender());
+        let result = contract.lock(ALICE, U256::from(1), U256::from(8));
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+        let result = contract.release(bet);
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+        let result = contract.settle(bet, BOB, U256::from(1));
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+        assert_eq!(contract.locked_balance_of(ALICE), U256::from(60));
+    }
+
+    /// Test that an admin can free the locks of a revoked engine.
+    #[motsu::test]
+    fn admin_force_releases_locks(contract: Erc20Aton) {
+        setup(contract);
+        let bet = U256::from(7);
+        assert!(contract.lock(ALICE, U256::from(60), bet).is_ok());
+        contract._revoke_role(ENGINE_ROLE, msg::sender());
+
+        assert!(contract.force_release(bet).is_ok());
+        assert_eq!(contract.locked_balance_of(ALICE), U256::ZERO);
+        assert!(!contract.bet_lock(bet).3);
+        let result = contract.force_release(bet);
+        assert!(matches!(result, Err(Error::BetNotLocked(_))));
+
+        contract._revoke_role(DEFAULT_ADMIN_ROLE, msg::sender());
+        let result = contract.force_release(U256::from(8));
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+    }
+}
@@ -0,0 +1,106 @@
+// tests/erc1363_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{ALICE, BOB};
+    use crate::test::mock_receiver;
+    use crate::{Erc20Aton, Error, ON_APPROVAL_RECEIVED, ON_TRANSFER_RECEIVED};
+    use stylus_sdk::{
+        abi::Bytes,
+        alloy_primitives::{Address, FixedBytes, U256},
+        msg,
+    };
+
+    // ERC-1363 receiver used for testing
+    const RECEIVER: Address = ALICE;
+
+    /// Test that the callback selectors match the ERC-1363 interface ids.
+    #[motsu::test]
+    fn callback_selectors(_contract: Erc20Aton) {
+        assert_eq!(ON_TRANSFER_RECEIVED, FixedBytes([0x88, 0xa7, 0xca, 0x5c]));
+        assert_eq!(ON_APPROVAL_RECEIVED, FixedBytes([0x7b, 0x04, 0xa2, 0xd0]));
+    }
+
+    /// Test that `transferAndCall` moves the tokens and notifies the receiver.
+    #[motsu::test]
+    fn transfer_and_call_notifies_receiver(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(contract._mint(sender, U256::from(100)).is_ok());
+        mock_receiver::set_transfer_response(Some(ON_TRANSFER_RECEIVED));
+
+        let data = Bytes::from(vec![7, 7]);
+        let result = contract.transfer_and_call_with_data(RECEIVER, U256::from(40), data);
+        assert_eq!(result.ok(), Some(true));
+
+        assert_eq!(contract.balance_of(sender), U256::from(60));
+        assert_eq!(contract.balance_of(RECEIVER), U256::from(40));
+
+        let transfers = mock_receiver::transfers();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].receiver, RECEIVER);
+        assert_eq!(transfers[0].operator, sender);
+        assert_eq!(transfers[0].from, sender);
+        assert_eq!(transfers[0].value, U256::from(40));
+        assert_eq!(transfers[0].data, vec![7, 7]);
+    }
+
+    /// Test that `transferFromAndCall` spends the allowance and reports the
+    /// holder as `from`.
+    #[motsu::test]
+    fn transfer_from_and_call_spends_allowance(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(contract._mint(BOB, U256::from(100)).is_ok());
+        assert!(contract._approve(BOB, sender, U256::from(50), true).is_ok());
+        mock_receiver::set_transfer_response(Some(ON_TRANSFER_RECEIVED));
+
+        let result = contract.transfer_from_and_call(BOB, RECEIVER, U256::from(30));
+        assert_eq!(result.ok(), Some(true));
+
+        assert_eq!(contract.balance_of(BOB), U256::from(70));
+        assert_eq!(contract.balance_of(RECEIVER), U256::from(30));
+        assert_eq!(contract.allowance(BOB, sender), U256::from(20));
+
+        let transfers = mock_receiver::transfers();
+        assert_eq!(transfers[0].operator, sender);
+        assert_eq!(transfers[0].from, BOB);
+        assert!(transfers[0].data.is_empty());
+    }
+
+    /// Test that a receiver returning the wrong value or reverting is rejected.
+    #[motsu::test]
+    fn transfer_and_call_rejected_by_receiver(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(contract._mint(sender, U256::from(100)).is_ok());
+
+        mock_receiver::set_transfer_response(Some(FixedBytes([0xde, 0xad, 0xbe, 0xef])));
+        let result = contract.transfer_and_call(RECEIVER, U256::from(10));
+        assert!(matches!(result, Err(Error::InvalidCallReceiver(_))));
+
+        mock_receiver::set_transfer_response(None);
+        let result = contract.transfer_and_call(RECEIVER, U256::from(10));
+        assert!(matches!(result, Err(Error::InvalidCallReceiver(_))));
+    }
+
+    /// Test that `approveAndCall` sets the allowance and notifies the spender.
+    #[motsu::test]
+    fn approve_and_call_notifies_spender(contract: Erc20Aton) {
+        let sender = msg::sender();
+        mock_receiver::set_approval_response(Some(ON_APPROVAL_RECEIVED));
+
+        let data = Bytes::from(vec![1]);
+        let result = contract.approve_and_call_with_data(RECEIVER, U256::from(25), data);
+        assert_eq!(result.ok(), Some(true));
+        assert_eq!(contract.allowance(sender, RECEIVER), U256::from(25));
+
+        let approvals = mock_receiver::approvals();
+        assert_eq!(approvals.len(), 1);
+        assert_eq!(approvals[0].spender, RECEIVER);
+        assert_eq!(approvals[0].owner, sender);
+        assert_eq!(approvals[0].value, U256::from(25));
+        assert_eq!(approvals[0].data, vec![1]);
+
+        mock_receiver::set_approval_response(None);
+        let result = contract.approve_and_call(RECEIVER, U256::from(5));
+        assert!(matches!(result, Err(Error::InvalidCallSpender(_))));
+    }
+}
@@ -2,10 +2,12 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::Erc20Aton;
+    use crate::test::helpers::{self, ALICE, BOB, VAULT};
+    use crate::test::mock_vault;
+    use crate::{Erc20Aton, Error, MINTER_ROLE, PAUSE_TRANSFERS, VAULT_MANAGER_ROLE};
     use stylus_sdk::{
-        alloy_primitives::{address, Address, U256}, msg,
-        prelude::*,
+        alloy_primitives::{address, Address, U256, U8},
+        block, msg,
     };
 
     // Vault address constant used for testing
@@ -16,6 +18,8 @@ mod tests {
     /// Test the ERC20 contract parameters such as name, symbol, decimals, and vault address.
     #[motsu::test]
     fn erc20params(contract: Erc20Aton) {
+        assert!(helpers::initialize(contract));
+
         let name = contract.name();
         let symbol = contract.symbol();
         let decimals = contract.decimals();
@@ -37,7 +41,7 @@ mod tests {
     #[motsu::test]
     fn initialize(contract: Erc20Aton) {
         // Ensure the contract initializes successfully
-        assert!(contract.initialize());
+        assert!(helpers::initialize(contract));
         
         // Check and print the owner of the contract after initialization
         let owner = contract.owner();
@@ -45,6 +49,38 @@ mod tests {
         assert!(!owner.is_zero()); // Owner should not be zero after initialization
     }
 
+    /// Test that initialization sets custom metadata, the vault and the
+    /// initial engines, and cannot run twice.
+    #[motsu::test]
+    fn initialize_with_parameters(contract: Erc20Aton) {
+        let vault: Address = VAULT_ADDRESS.parse().unwrap();
+        let engine: Address = ARENATON_ENGINE.parse().unwrap();
+
+        let result = contract.initialize(
+            "ATON Testnet".into(),
+            "tATON".into(),
+            6,
+            vault,
+            vec![engine, Address::ZERO],
+        );
+        assert!(matches!(result, Err(Error::Zero(_))));
+        assert!(contract.owner().is_zero());
+
+        let result =
+            contract.initialize("ATON Testnet".into(), "tATON".into(), 6, vault, vec![engine]);
+        assert!(result.is_ok());
+        assert_eq!(contract.name(), "ATON Testnet");
+        assert_eq!(contract.symbol(), "tATON");
+        assert_eq!(contract.decimals(), 6);
+        assert_eq!(contract.vault_address(), vault);
+        assert!(contract.is_stake_engine(engine));
+        assert_eq!(contract.owner(), msg::sender());
+
+        let result = contract.initialize("Other".into(), "OTH".into(), 18, Address::ZERO, vec![]);
+        assert!(matches!(result, Err(Error::InvalidInitialization(_))));
+        assert_eq!(contract.name(), "ATON Testnet");
+    }
+
     /// Test the functionality for setting the vault address.
     #[motsu::test]
     fn set_vault(contract: Erc20Aton) {
@@ -59,13 +95,99 @@ mod tests {
         // Assert that the parsed address matches the expected address
         assert_eq!(parsed, expected);
 
+        // Only the owner can set the vault
+        let result = contract.set_vault(parsed);
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+
         // Set the vault address in the contract
-        contract.set_vault(parsed);
+        assert!(helpers::initialize(contract));
+        assert!(contract.set_vault(parsed).is_ok());
 
         // Verify that the vault address was correctly set
         assert_eq!(contract.vault_address(), parsed);
+
+        // The vault can be rotated, but never to the zero address
+        let rotated = address!("00000000000000000000000000000000000a11ce");
+        assert!(contract.set_vault(rotated).is_ok());
+        assert_eq!(contract.vault_address(), rotated);
+        let result = contract.set_vault(Address::ZERO);
+        assert!(matches!(result, Err(Error::Zero(_))));
     }
 
+    /// Test that a vault delay schedules rotations behind a timelock.
+    #[motsu::test]
+    fn set_vault_with_delay(contract: Erc20Aton) {
+        let rotated = ALICE;
+        assert!(helpers::initialize_with_vault(contract, 0));
+
+        let result = contract.execute_vault_update();
+        assert!(matches!(result, Err(Error::NoPendingVaultUpdate(_))));
+
+        assert!(contract.set_vault_delay(U256::from(86_400)).is_ok());
+        assert_eq!(contract.vault_delay(), U256::from(86_400));
+        assert!(contract.set_vault(rotated).is_ok());
+        assert_eq!(contract.vault_address(), VAULT);
+        assert_eq!(contract.pending_vault(), rotated);
+        let eta = U256::from(block::timestamp() + 86_400);
+        assert_eq!(contract.vault_update_eta(), eta);
+
+        let result = contract.execute_vault_update();
+        assert!(matches!(result, Err(Error::VaultUpdateNotReady(_))));
+
+        // Move the deadline into the past, as if the delay had elapsed
+        contract.vault_update_eta.set(U256::from(block::timestamp()));
+        assert!(contract.execute_vault_update().is_ok());
+        assert_eq!(contract.vault_address(), rotated);
+        assert!(contract.pending_vault().is_zero());
+
+        // A pending update can be canceled
+        assert!(contract.set_vault(VAULT).is_ok());
+        assert!(contract.cancel_vault_update().is_ok());
+        assert!(contract.pending_vault().is_zero());
+        assert_eq!(contract.vault_address(), rotated);
+        let result = contract.cancel_vault_update();
+        assert!(matches!(result, Err(Error::NoPendingVaultUpdate(_))));
+    }
+
+    /// Test that the vault delay cannot be lowered to skip the timelock.
+    #[motsu::test]
+    fn vault_delay_decrease_is_timelocked(contract: Erc20Aton) {
+        let rotated = ALICE;
+        assert!(helpers::initialize(contract));
+        assert!(contract.set_vault_delay(U256::from(86_400)).is_ok());
+
+        // Dropping the delay only schedules the decrease
+        assert!(contract.set_vault_delay(U256::ZERO).is_ok());
+        assert_eq!(contract.vault_delay(), U256::from(86_400));
+        let eta = U256::from(block::timestamp() + 86_400);
+        assert_eq!(contract.pending_vault_delay(), (U256::ZERO, eta));
+        assert!(contract.set_vault(VAULT).is_ok());
+        assert!(contract.vault_address().is_zero());
+
+        let result = contract.execute_vault_delay_update();
+        assert!(matches!(result, Err(Error::VaultUpdateNotReady(_))));
+
+        // Move the deadline into the past, as if the delay had elapsed
+        contract.vault_delay_eta.set(U256::from(block::timestamp()));
+        assert!(contract.execute_vault_delay_update().is_ok());
+        assert!(contract.vault_delay().is_zero());
+        let result = contract.execute_vault_delay_update();
+        assert!(matches!(result, Err(Error::NoPendingVaultUpdate(_))));
+
+        // An immediate rotation discards the stale pending update
+        assert!(contract.set_vault(rotated).is_ok());
+        assert_eq!(contract.vault_address(), rotated);
+        assert!(contract.pending_vault().is_zero());
+        let result = contract.execute_vault_update();
+        assert!(matches!(result, Err(Error::NoPendingVaultUpdate(_))));
+
+        // An increase applies at once and drops a pending decrease
+        assert!(contract.set_vault_delay(U256::from(3_600)).is_ok());
+        assert!(contract.set_vault_delay(U256::from(60)).is_ok());
+        assert!(contract.set_vault_delay(U256::from(7_200)).is_ok());
+        assert_eq!(contract.vault_delay(), U256::from(7_200));
+        assert_eq!(contract.pending_vault_delay(), (U256::ZERO, U256::ZERO));
+    }
 
     /// Test updating and verifying the ArenaTon engine functionality.
     #[motsu::test]
@@ -76,7 +198,7 @@ mod tests {
         assert!(!contract.is_stake_engine(sender));
 
         // Initialize the contract
-        assert!(contract.initialize());
+        assert!(helpers::initialize(contract));
 
         // Verify that the sender is the owner after initialization
         assert!(sender == contract.owner());
@@ -87,4 +209,219 @@ mod tests {
         // Verify that the sender is now recognized as a stake engine
         assert!(contract.is_stake_engine(sender));
     }
+
+    /// Test that only stake engines can mint.
+    #[motsu::test]
+    fn mint_requires_stake_engine(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(helpers::initialize(contract));
+
+        let result = contract._engine_mint(sender, U256::from(1));
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+        assert_eq!(contract.balance_of(sender), U256::ZERO);
+    }
+
+    /// Test that engine mints are bounded by the quota set by the owner.
+    #[motsu::test]
+    fn mint_respects_engine_quota(contract: Erc20Aton) {
+        let engine = msg::sender();
+        assert!(helpers::initialize(contract));
+        assert!(contract.update_stake_engine(engine, true).is_ok());
+
+        // Engines start without any quota
+        let result = contract._engine_mint(engine, U256::from(1));
+        assert!(matches!(result, Err(Error::MintQuotaExceeded(_))));
+
+        assert!(contract.grant_role(MINTER_ROLE, engine).is_ok());
+
+        assert!(contract.set_mint_quota(engine, U256::from(100)).is_ok());
+        assert_eq!(contract.remaining_mint_quota(engine), U256::from(100));
+
+        assert!(contract._engine_mint(engine, U256::from(60)).is_ok());
+        assert_eq!(contract.remaining_mint_quota(engine), U256::from(40));
+        assert_eq!(contract.balance_of(engine), U256::from(60));
+        assert_eq!(contract.total_supply(), U256::from(60));
+
+        let result = contract._engine_mint(engine, U256::from(41));
+        assert!(matches!(result, Err(Error::MintQuotaExceeded(_))));
+        assert_eq!(contract.remaining_mint_quota(engine), U256::from(40));
+    }
+
+    /// Test that accumulated commission is shared pro rata and survives transfers.
+    #[motsu::test]
+    fn commission_accrues_pro_rata(contract: Erc20Aton) {
+        let sender = msg::sender();
+
+        assert!(contract._mint(ALICE, U256::from(300)).is_ok());
+        assert!(contract._mint(BOB, U256::from(100)).is_ok());
+        assert!(contract._mint(sender, U256::from(40)).is_ok());
+
+        // 40 tokens shared over the 400 still held by alice and bob
+        assert!(contract.accumulate_commission(U256::from(40)).is_ok());
+        assert_eq!(contract.pending_commission(ALICE).ok(), Some(U256::from(30)));
+        assert_eq!(contract.pending_commission(BOB).ok(), Some(U256::from(10)));
+        assert_eq!(contract.pending_commission(sender).ok(), Some(U256::ZERO));
+
+        // Moving balances settles what was earned before the transfer
+        assert!(contract._transfer(ALICE, BOB, U256::from(300)).is_ok());
+        assert_eq!(contract.pending_commission(ALICE).ok(), Some(U256::from(30)));
+        assert_eq!(contract.pending_commission(BOB).ok(), Some(U256::from(10)));
+    }
+
+    /// Test that claiming pays out the pending commission exactly once.
+    #[motsu::test]
+    fn claim_commission(contract: Erc20Aton) {
+        let sender = msg::sender();
+
+        assert!(contract._mint(ALICE, U256::from(100)).is_ok());
+        assert!(contract._mint(sender, U256::from(150)).is_ok());
+        assert!(contract.accumulate_commission(U256::from(50)).is_ok());
+
+        // The sender still holds 100 of the 200 eligible tokens
+        assert_eq!(contract.pending_commission(sender).ok(), Some(U256::from(25)));
+        assert_eq!(contract.claim_commission().ok(), Some(U256::from(25)));
+        assert_eq!(contract.balance_of(sender), U256::from(125));
+        assert_eq!(contract.pending_commission(sender).ok(), Some(U256::ZERO));
+        assert_eq!(contract.claim_commission().ok(), Some(U256::ZERO));
+        assert_eq!(contract.pending_commission(ALICE).ok(), Some(U256::from(25)));
+    }
+
+    /// Test that commission cannot be accumulated without eligible holders.
+    #[motsu::test]
+    fn accumulate_commission_requires_holders(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(contract._mint(sender, U256::from(10)).is_ok());
+
+        let result = contract.accumulate_commission(U256::ZERO);
+        assert!(matches!(result, Err(Error::Zero(_))));
+
+        let result = contract.accumulate_commission(U256::from(10));
+        assert!(matches!(result, Err(Error::Zero(_))));
+    }
+
+    /// Test that overflowing commission accounting fails instead of wrapping.
+    #[motsu::test]
+    fn commission_overflow_is_rejected(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(contract._mint(ALICE, U256::from(100)).is_ok());
+        assert!(contract._mint(sender, U256::from(10)).is_ok());
+
+        contract.acc_commission_per_token.set(U256::MAX / U256::from(10));
+        let result = contract.pending_commission(ALICE);
+        assert!(matches!(result, Err(Error::CommissionOverflow(_))));
+        let result = contract._transfer(ALICE, sender, U256::from(1));
+        assert!(matches!(result, Err(Error::CommissionOverflow(_))));
+        assert_eq!(contract.balance_of(ALICE), U256::from(100));
+
+        // The sender is settled, but the accumulator itself would overflow
+        let acc = U256::MAX - U256::from(1);
+        contract.acc_commission_per_token.set(acc);
+        contract.commission_debt.setter(sender).set(acc);
+        let result = contract.accumulate_commission(U256::from(10));
+        assert!(matches!(result, Err(Error::CommissionOverflow(_))));
+    }
+
+    /// Test that strict settlement reverts a transfer when the vault call fails.
+    #[motsu::test]
+    fn strict_settlement_fails_closed(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let receiver = ALICE;
+
+        assert!(helpers::initialize_with_vault(contract, 0));
+        assert!(contract._mint(sender, U256::from(100)).is_ok());
+        assert!(contract.grant_role(VAULT_MANAGER_ROLE, msg::sender()).is_ok());
+        assert!(contract.set_strict_settlement(true).is_ok());
+        assert!(contract.strict_settlement());
+
+        mock_vault::fail_player_commission(true);
+        let result = contract.transfer(receiver, U256::from(10));
+        assert!(matches!(result, Err(Error::VaultCallFailed(_))));
+        assert_eq!(contract.balance_of(receiver), U256::ZERO);
+    }
+
+    /// Test that lenient settlement skips vault failures.
+    #[motsu::test]
+    fn lenient_settlement_skips_vault_failures(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let receiver = ALICE;
+
+        assert!(helpers::initialize_with_vault(contract, 0));
+        assert!(contract._mint(sender, U256::from(100)).is_ok());
+        assert!(!contract.strict_settlement());
+
+        mock_vault::fail_player_commission(true);
+        assert!(contract.transfer(receiver, U256::from(10)).is_ok());
+        assert_eq!(contract.balance_of(receiver), U256::from(10));
+    }
+
+    /// Test that lenient settlement only clears commissions it paid.
+    #[motsu::test]
+    fn lenient_settlement_keeps_unpaid_commission(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let receiver = ALICE;
+
+        assert!(helpers::initialize_with_vault(contract, 5));
+        assert!(contract._mint(sender, U256::from(100)).is_ok());
+        mock_vault::set_commission(receiver, U256::from(3));
+        mock_vault::set_commission(sender, U256::from(10));
+
+        assert!(contract.transfer(receiver, U256::from(10)).is_ok());
+        assert_eq!(contract.balance_of(receiver), U256::from(13));
+        assert_eq!(contract.balance_of(sender), U256::from(90));
+        assert_eq!(contract.balance_of(VAULT), U256::from(2));
+        assert_eq!(mock_vault::cleared(), vec![receiver]);
+    }
+
+    /// Test that lenient settlement does not pay a commission it could not clear.
+    #[motsu::test]
+    fn lenient_settlement_skips_uncleared_commission(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let receiver = ALICE;
+
+        assert!(helpers::initialize_with_vault(contract, 5));
+        assert!(contract._mint(sender, U256::from(100)).is_ok());
+        mock_vault::set_commission(receiver, U256::from(3));
+        mock_vault::fail_clear_commission(true);
+
+        assert!(contract.transfer(receiver, U256::from(10)).is_ok());
+        assert_eq!(contract.balance_of(receiver), U256::from(10));
+        assert_eq!(contract.balance_of(VAULT), U256::from(5));
+        assert!(mock_vault::cleared().is_empty());
+    }
+
+    /// Test that a failed commission payment keeps its underlying error.
+    #[motsu::test]
+    fn settlement_propagates_payment_error(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let receiver = ALICE;
+
+        assert!(helpers::initialize_with_vault(contract, 5));
+        assert!(contract._mint(sender, U256::from(100)).is_ok());
+        // The owner cannot pause, so set the flag directly
+        contract.paused.set(U8::from(PAUSE_TRANSFERS));
+        mock_vault::set_commission(receiver, U256::from(3));
+
+        let result = contract.transfer(receiver, U256::from(10));
+        assert!(matches!(result, Err(Error::EnforcedPause(_))));
+    }
+
+    /// Test that strict settlement only counts the vault's spendable balance.
+    #[motsu::test]
+    fn strict_settlement_ignores_locked_vault_balance(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let receiver = ALICE;
+
+        assert!(helpers::initialize_with_vault(contract, 10));
+        assert!(contract._mint(sender, U256::from(100)).is_ok());
+        assert!(contract.update_stake_engine(sender, true).is_ok());
+        assert!(contract._approve(VAULT, sender, U256::from(8), true).is_ok());
+        assert!(contract.lock(VAULT, U256::from(8), U256::from(1)).is_ok());
+        assert!(contract.grant_role(VAULT_MANAGER_ROLE, sender).is_ok());
+        assert!(contract.set_strict_settlement(true).is_ok());
+        mock_vault::set_commission(receiver, U256::from(5));
+
+        let result = contract.transfer(receiver, U256::from(10));
+        assert!(matches!(result, Err(Error::VaultUnderfunded(_))));
+        assert!(mock_vault::cleared().is_empty());
+    }
 }
@@ -0,0 +1,282 @@
+// tests/exchange_rate_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::{helpers, mock_price_feed, shims};
+    use crate::{Erc20Aton, Error, MINTER_ROLE, RATE_PRECISION};
+    use stylus_sdk::{
+        alloy_primitives::{address, Address, I256, U256},
+        block, contract, msg,
+    };
+
+    // Price feed address used for testing
+    const FEED: Address = address!("00000000000000000000000000000000000fee0d");
+
+    /// Initializes the token and lets `msg::sender()` mint up to `quota`.
+    fn setup_engine(contract: &mut Erc20Aton, quota: u64) {
+        let engine = msg::sender();
+        assert!(helpers::initialize(contract));
+        assert!(contract.update_stake_engine(engine, true).is_ok());
+        assert!(contract.grant_role(MINTER_ROLE, engine).is_ok());
+        assert!(contract.set_mint_quota(engine, U256::from(quota)).is_ok());
+    }
+
+    /// Test that the rate defaults to one-to-one and can be configured.
+    #[motsu::test]
+    fn configurable_exchange_rate(contract: Erc20Aton) {
+        assert!(helpers::initialize(contract));
+        assert_eq!(contract.exchange_rate().ok(), Some(RATE_PRECISION));
+        assert_eq!(contract.quote_mint(U256::from(100)).ok(), Some(U256::from(100)));
+
+        // 2.5 ATON per ETH
+        let rate = RATE_PRECISION * U256::from(5) / U256::from(2);
+        assert!(contract.set_exchange_rate(rate).is_ok());
+        assert_eq!(contract.quote_mint(U256::from(100)).ok(), Some(U256::from(250)));
+        assert_eq!(contract.quote_swap(U256::from(250)).ok(), Some(U256::from(100)));
+        // Both directions round down
+        assert_eq!(contract.quote_swap(U256::from(3)).ok(), Some(U256::from(1)));
+
+        let result = contract.set_exchange_rate(U256::ZERO);
+        assert!(matches!(result, Err(Error::InvalidExchangeRate(_))));
+    }
+
+    /// Test that `mint_aton` and `swap` convert at the configured rate.
+    #[motsu::test]
+    fn mint_and_swap_at_exchange_rate(contract: Erc20Aton) {
+        let engine = msg::sender();
+        setup_engine(contract, 1_000);
+        assert!(contract.set_exchange_rate(RATE_PRECISION * U256::from(2)).is_ok());
+
+        shims::set_msg_value(U256::from(100));
+        assert_eq!(contract.mint_aton_min_out(U256::from(200)).ok(), Some(U256::from(200)));
+        assert_eq!(contract.balance_of(engine), U256::from(200));
+        assert_eq!(contract.remaining_mint_quota(engine), U256::from(800));
+
+        shims::set_eth_balance(contract::address(), U256::from(100));
+        assert_eq!(contract.swap_min_out(U256::from(50), U256::from(25)).ok(), Some(U256::from(25)));
+        assert_eq!(contract.balance_of(engine), U256::from(150));
+        assert_eq!(contract::balance(), U256::from(75));
+
+        // A swap too small to pay out any wei is rejected
+        let result = contract.swap(U256::from(1));
+        assert!(matches!(result, Err(Error::ZeroSwapAmount(_))));
+    }
+
+    /// Test that the slippage-protected variants enforce their minimum.
+    #[motsu::test]
+    fn min_out_rejects_slippage(contract: Erc20Aton) {
+        let engine = msg::sender();
+        setup_engine(contract, 1_000);
+        shims::set_msg_value(U256::from(100));
+
+        let result = contract.mint_aton_min_out(U256::from(101));
+        assert!(matches!(result, Err(Error::SlippageExceeded(_))));
+        assert_eq!(contract.balance_of(engine), U256::ZERO);
+
+        assert!(contract.mint_aton().is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(100));
+        let result = contract.swap_min_out(U256::from(50), U256::from(51));
+        assert!(matches!(result, Err(Error::SlippageExceeded(_))));
+        assert_eq!(contract.balance_of(engine), U256::from(100));
+    }
+
+    /// Test that a price feed overrides the configured rate.
+    #[motsu::test]
+    fn price_feed_sets_exchange_rate(contract: Erc20Aton) {
+        assert!(helpers::initialize(contract));
+        assert!(contract.set_exchange_rate(RATE_PRECISION * U256::from(7)).is_ok());
+
+        // 3.5 ATON per ETH with 8 decimals, reported a minute ago
+        let now = U256::from(block::timestamp());
+        mock_price_feed::set_price(I256::try_from(350_000_000).unwrap(), now - U256::from(60), 8);
+        assert!(contract.set_price_feed(FEED, U256::from(3_600)).is_ok());
+        assert_eq!(contract.price_feed(), FEED);
+        assert_eq!(contract.max_price_age(), U256::from(3_600));
+
+        let rate = RATE_PRECISION * U256::from(7) / U256::from(2);
+        assert_eq!(contract.exchange_rate().ok(), Some(rate));
+        assert_eq!(contract.quote_mint(U256::from(10)).ok(), Some(U256::from(35)));
+
+        // Removing the feed restores the configured rate
+        assert!(contract.set_price_feed(Address::ZERO, U256::ZERO).is_ok());
+        assert_eq!(contract.exchange_rate().ok(), Some(RATE_PRECISION * U256::from(7)));
+    }
+
+    /// Test that stale, non-positive and unreadable feed answers are rejected.
+    #[motsu::test]
+    fn price_feed_rejects_bad_answers(contract: Erc20Aton) {
+        assert!(helpers::initialize(contract));
+        let now = U256::from(block::timestamp());
+        mock_price_feed::set_price(I256::try_from(100).unwrap(), now, 2);
+        assert!(contract.set_price_feed(FEED, U256::from(3_600)).is_ok());
+
+        mock_price_feed::set_price(I256::try_from(100).unwrap(), now - U256::from(3_601), 2);
+        assert!(matches!(contract.exchange_rate(), Err(Error::StalePrice(_))));
+
+        mock_price_feed::set_price(I256::ZERO, now, 2);
+        assert!(matches!(contract.exchange_rate(), Err(Error::InvalidPrice(_))));
+        mock_price_feed::set_price(I256::MINUS_ONE, now, 2);
+        assert!(matches!(contract.quote_swap(U256::from(1)), Err(Error::InvalidPrice(_))));
+
+        // Answers that overflow once scaled are rejected instead of wrapping
+        mock_price_feed::set_price(I256::MAX, now, 2);
+        assert!(matches!(contract.exchange_rate(), Err(Error::InvalidPrice(_))));
+        mock_price_feed::set_price(I256::try_from(100).unwrap(), now, 78);
+        assert!(matches!(contract.exchange_rate(), Err(Error::InvalidPrice(_))));
+
+        mock_price_feed::fail(true);
+        assert!(matches!(contract.exchange_rate(), Err(Error::PriceFeedCallFailed(_))));
+    }
+
+    /// Test that a feed is only accepted with a maximum age and a usable
+    /// answer.
+    #[motsu::test]
+    fn set_price_feed_validates_feed(contract: Erc20Aton) {
+        assert!(helpers::initialize(contract));
+        let now = U256::from(block::timestamp());
+        mock_price_feed::set_price(I256::try_from(100).unwrap(), now, 2);
+
+        let result = contract.set_price_feed(FEED, U256::ZERO);
+        assert!(matches!(result, Err(Error::InvalidMaxPriceAge(_))));
+
+        mock_price_feed::set_price(I256::ZERO, now, 2);
+        let result = contract.set_price_feed(FEED, U256::from(3_600));
+        assert!(matches!(result, Err(Error::InvalidPrice(_))));
+        mock_price_feed::set_price(I256::MINUS_ONE, now, 2);
+        let result = contract.set_price_feed(FEED, U256::from(3_600));
+        assert!(matches!(result, Err(Error::InvalidPrice(_))));
+
+        // A feed that never reported is stale whatever the maximum age
+        mock_price_feed::set_price(I256::try_from(100).unwrap(), U256::ZERO, 2);
+        let result = contract.set_price_feed(FEED, U256::MAX);
+        assert!(matches!(result, Err(Error::StalePrice(_))));
+        assert!(contract.price_feed().is_zero());
+
+        // Removing the feed needs no maximum age
+        assert!(contract.set_price_feed(Address::ZERO, U256::ZERO).is_ok());
+    }
+
+    /// Test that quotes overflowing `U256` are rejected instead of wrapping.
+    #[motsu::test]
+    fn quotes_reject_overflow(contract: Erc20Aton) {
+        assert!(helpers::initialize(contract));
+
+        let result = contract.quote_swap(U256::MAX);
+        assert!(matches!(result, Err(Error::QuoteOverflow(_))));
+
+        assert!(contract.set_exchange_rate(U256::MAX).is_ok());
+        assert_eq!(contract.quote_mint(U256::from(1)).ok(), Some(U256::MAX / RATE_PRECISION));
+        let result = contract.quote_mint(U256::from(2));
+        assert!(matches!(result, Err(Error::QuoteOverflow(_))));
+    }
+
+    /// Test that only the owner can configure the rate.
+    #[motsu::test]
+    fn exchange_rate_is_owner_only(contract: Erc20Aton) {
+        let result = contract.set_exchange_rate(RATE_PRECISION);
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+        let result = contract.set_price_feed(FEED, U256::from(60));
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+    }
+
+    /// Test that mints and swaps are recorded in the reserve totals.
+    #[motsu::test]
+    fn reserve_tracks_mints_and_swaps(contract: Erc20Aton) {
+        setup_engine(contract, 1_000);
+        assert!(contract.set_exchange_rate(RATE_PRECISION * U256::from(2)).is_ok());
+
+        shims::set_msg_value(U256::from(100));
+        assert!(contract.mint_aton().is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(100));
+        assert!(contract.swap(U256::from(60)).is_ok());
+
+        let totals = (U256::from(100), U256::from(30), U256::ZERO);
+        assert_eq!(contract.reserve_totals(), totals);
+        assert_eq!(contract::balance(), U256::from(70));
+        assert_eq!(contract.backing_of(contract.total_supply()).ok(), Some(U256::from(70)));
+        assert_eq!(contract.reserve_ratio().ok(), Some(RATE_PRECISION));
+        assert_eq!(contract.excess_eth().ok(), Some(U256::ZERO));
+    }
+
+    /// Test that only ETH beyond the supply's backing can be swept.
+    #[motsu::test]
+    fn sweep_excess_keeps_supply_backed(contract: Erc20Aton) {
+        let treasury = address!("000000000000000000000000000000000000beef");
+        setup_engine(contract, 1_000);
+        shims::set_msg_value(U256::from(100));
+        assert!(contract.mint_aton().is_ok());
+        // 100 wei deposited by the mint plus 50 wei sent directly
+        shims::set_eth_balance(contract::address(), U256::from(150));
+
+        assert_eq!(contract.required_reserve().ok(), Some(U256::from(100)));
+        assert_eq!(contract.reserve_ratio().ok(), Some(RATE_PRECISION * U256::from(3) / U256::from(2)));
+        assert_eq!(contract.excess_eth().ok(), Some(U256::from(50)));
+
+        let result = contract.sweep_excess(treasury, U256::from(51));
+        assert!(matches!(result, Err(Error::InsufficientExcess(_))));
+        let result = contract.sweep_excess(Address::ZERO, U256::from(1));
+        assert!(matches!(result, Err(Error::Zero(_))));
+
+        assert!(contract.sweep_excess(treasury, U256::from(50)).is_ok());
+        assert_eq!(contract::balance(), U256::from(100));
+        assert_eq!(contract.excess_eth().ok(), Some(U256::ZERO));
+        assert_eq!(contract.reserve_totals().2, U256::from(50));
+    }
+
+    /// Test that raising the exchange rate cannot free deposited ETH, while
+    /// lowering it raises the reserve needed.
+    #[motsu::test]
+    fn rate_change_cannot_free_backing(contract: Erc20Aton) {
+        let treasury = address!("000000000000000000000000000000000000beef");
+        setup_engine(contract, 1_000);
+        shims::set_msg_value(U256::from(100));
+        assert!(contract.mint_aton().is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(100));
+
+        assert!(contract.set_exchange_rate(RATE_PRECISION * U256::from(4)).is_ok());
+        assert_eq!(contract.backing_of(contract.total_supply()).ok(), Some(U256::from(25)));
+        assert_eq!(contract.required_reserve().ok(), Some(U256::from(100)));
+        assert_eq!(contract.excess_eth().ok(), Some(U256::ZERO));
+        let result = contract.sweep_excess(treasury, U256::from(1));
+        assert!(matches!(result, Err(Error::InsufficientExcess(_))));
+        assert_eq!(contract::balance(), U256::from(100));
+
+        assert!(contract.set_exchange_rate(RATE_PRECISION / U256::from(2)).is_ok());
+        assert_eq!(contract.required_reserve().ok(), Some(U256::from(200)));
+        assert_eq!(contract.reserve_ratio().ok(), Some(RATE_PRECISION / U256::from(2)));
+    }
+
+    /// Test that deposits left over after a rate increase become excess once
+    /// the whole supply is redeemed.
+    #[motsu::test]
+    fn redeemed_supply_frees_leftover_deposits(contract: Erc20Aton) {
+        let engine = msg::sender();
+        let treasury = address!("000000000000000000000000000000000000beef");
+        setup_engine(contract, 1_000);
+        shims::set_msg_value(U256::from(100));
+        assert!(contract.mint_aton().is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(100));
+
+        // Swaps at the higher rate pay out half of what was deposited
+        assert!(contract.set_exchange_rate(RATE_PRECISION * U256::from(2)).is_ok());
+        assert!(contract.swap(U256::from(60)).is_ok());
+        assert_eq!(contract.required_reserve().ok(), Some(U256::from(70)));
+        assert_eq!(contract.excess_eth().ok(), Some(U256::ZERO));
+
+        assert!(contract.swap(contract.balance_of(engine)).is_ok());
+        assert!(contract.total_supply().is_zero());
+        assert_eq!(contract::balance(), U256::from(50));
+        assert_eq!(contract.required_reserve().ok(), Some(U256::ZERO));
+        assert_eq!(contract.excess_eth().ok(), Some(U256::from(50)));
+        assert!(contract.sweep_excess(treasury, U256::from(50)).is_ok());
+        assert_eq!(contract::balance(), U256::ZERO);
+    }
+
+    /// Test that only the owner can sweep.
+    #[motsu::test]
+    fn sweep_excess_is_owner_only(contract: Erc20Aton) {
+        let result = contract.sweep_excess(msg::sender(), U256::ZERO);
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+        assert_eq!(contract.reserve_ratio().ok(), Some(U256::MAX));
+    }
+}
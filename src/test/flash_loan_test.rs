@@ -0,0 +1,183 @@
+// tests/flash_loan_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, OWNER, VAULT};
+    use crate::test::{mock_receiver, shims};
+    use crate::{
+        Erc20Aton, Error, FLASH_FEE_DENOMINATOR, FLASH_LOAN_CALLBACK_SUCCESS, PAUSER_ROLE,
+        PAUSE_MINT,
+    };
+    use stylus_sdk::{
+        abi::Bytes,
+        alloy_primitives::{Address, B256, U256},
+        contract, msg,
+    };
+
+    // Flash loan receiver used for testing
+    const BORROWER: Address = ALICE;
+
+    /// Test the ERC-3156 views for supported and unsupported tokens.
+    #[motsu::test]
+    fn max_flash_loan_and_fee(contract: Erc20Aton) {
+        let token = contract::address();
+        assert!(helpers::initialize_with_balance(contract, BORROWER, 10));
+
+        let cap = U256::MAX / U256::from(FLASH_FEE_DENOMINATOR);
+        assert_eq!(contract.max_flash_loan(token), cap);
+        assert_eq!(contract.max_flash_loan(VAULT), U256::ZERO);
+
+        assert_eq!(contract.flash_fee(token, U256::from(10_000)).ok(), Some(U256::ZERO));
+        assert!(contract.set_flash_fee(U256::from(30)).is_ok());
+        assert_eq!(contract.flash_fee(token, U256::from(10_000)).ok(), Some(U256::from(30)));
+
+        let result = contract.flash_fee(VAULT, U256::from(10_000));
+        assert!(matches!(result, Err(Error::UnsupportedToken(_))));
+
+        let result = contract.set_flash_fee(U256::from(10_001));
+        assert!(matches!(result, Err(Error::FlashFeeTooHigh(_))));
+
+        // Amounts whose fee would overflow are rejected instead of wrapping
+        let result = contract.flash_fee(token, U256::MAX);
+        assert!(matches!(result, Err(Error::ExceededMaxLoan(_))));
+        let result = contract.flash_loan(BORROWER, token, cap + U256::from(1), Bytes::from(vec![]));
+        assert!(matches!(result, Err(Error::ExceededMaxLoan(_))));
+    }
+
+    /// Test that the borrower can call back into the token from its hook to
+    /// use the loan and approve the repayment.
+    #[motsu::test]
+    fn flash_loan_hook_can_call_back(contract: Erc20Aton) {
+        let token = contract::address();
+        assert!(helpers::initialize(contract));
+        assert!(contract.set_flash_fee(U256::from(100)).is_ok());
+        assert!(contract._mint(BORROWER, U256::from(5)).is_ok());
+        mock_receiver::set_flash_loan_response(Some(FLASH_LOAN_CALLBACK_SUCCESS));
+        // Stands in for the borrower's own calls to the token
+        mock_receiver::set_flash_loan_hook(|contract, loan| {
+            assert_eq!(contract.balance_of(loan.receiver), loan.amount + U256::from(5));
+            let repayment = loan.amount + loan.fee;
+            assert!(contract._approve(loan.receiver, loan.token, repayment, true).is_ok());
+        });
+
+        assert!(contract.flash_loan(BORROWER, token, U256::from(500), Bytes::from(vec![])).is_ok());
+
+        assert_eq!(contract.balance_of(BORROWER), U256::ZERO);
+        assert_eq!(contract.allowance(BORROWER, token), U256::ZERO);
+        assert_eq!(contract.total_supply(), U256::ZERO);
+    }
+
+    /// Test that a flash loan is repaid and its fee routed to the vault.
+    #[motsu::test]
+    fn flash_loan_routes_fee_to_vault(contract: Erc20Aton) {
+        let token = contract::address();
+        assert!(helpers::initialize_with_vault(contract, 0));
+        assert!(contract.set_flash_fee(U256::from(30)).is_ok());
+
+        // The borrower holds the fee and approved the repayment upfront
+        assert!(contract._mint(BORROWER, U256::from(3)).is_ok());
+        assert!(contract._approve(BORROWER, token, U256::from(1_003), true).is_ok());
+        mock_receiver::set_flash_loan_response(Some(FLASH_LOAN_CALLBACK_SUCCESS));
+
+        let data = Bytes::from(vec![1, 2, 3]);
+        assert!(contract.flash_loan(BORROWER, token, U256::from(1_000), data).is_ok());
+
+        assert_eq!(contract.balance_of(BORROWER), U256::ZERO);
+        assert_eq!(contract.balance_of(VAULT), U256::from(3));
+        assert_eq!(contract.total_supply(), U256::from(3));
+        assert_eq!(contract.allowance(BORROWER, token), U256::ZERO);
+
+        let loans = mock_receiver::flash_loans();
+        assert_eq!(loans.len(), 1);
+        assert_eq!(loans[0].initiator, msg::sender());
+        assert_eq!(loans[0].amount, U256::from(1_000));
+        assert_eq!(loans[0].fee, U256::from(3));
+        assert_eq!(loans[0].data, vec![1, 2, 3]);
+    }
+
+    /// Test that the fee is burnt when no vault is set.
+    #[motsu::test]
+    fn flash_loan_burns_fee_without_vault(contract: Erc20Aton) {
+        let token = contract::address();
+        assert!(helpers::initialize(contract));
+        assert!(contract.set_flash_fee(U256::from(100)).is_ok());
+        assert!(contract._mint(BORROWER, U256::from(5)).is_ok());
+        assert!(contract._approve(BORROWER, token, U256::MAX, true).is_ok());
+        mock_receiver::set_flash_loan_response(Some(FLASH_LOAN_CALLBACK_SUCCESS));
+
+        assert!(contract.flash_loan(BORROWER, token, U256::from(500), Bytes::from(vec![])).is_ok());
+
+        assert_eq!(contract.balance_of(BORROWER), U256::ZERO);
+        assert_eq!(contract.total_supply(), U256::ZERO);
+    }
+
+    /// Test that flash loans are rejected when the receiver misbehaves.
+    #[motsu::test]
+    fn flash_loan_rejects_invalid_receiver(contract: Erc20Aton) {
+        let token = contract::address();
+        assert!(helpers::initialize(contract));
+
+        mock_receiver::set_flash_loan_response(Some(B256::ZERO));
+        let result = contract.flash_loan(BORROWER, token, U256::from(100), Bytes::from(vec![]));
+        assert!(matches!(result, Err(Error::InvalidFlashReceiver(_))));
+
+        mock_receiver::set_flash_loan_response(None);
+        let result = contract.flash_loan(BORROWER, token, U256::from(100), Bytes::from(vec![]));
+        assert!(matches!(result, Err(Error::InvalidFlashReceiver(_))));
+    }
+
+    /// Test that the borrower can transfer but not swap the loan from its
+    /// hook.
+    #[motsu::test]
+    fn flash_loan_blocks_reentrant_swap(contract: Erc20Aton) {
+        let token = contract::address();
+        assert!(helpers::initialize(contract));
+        assert!(contract._approve(BORROWER, token, U256::MAX, true).is_ok());
+        shims::set_eth_balance(token, U256::from(500));
+        mock_receiver::set_flash_loan_response(Some(FLASH_LOAN_CALLBACK_SUCCESS));
+        mock_receiver::set_flash_loan_hook(|contract, loan| {
+            assert!(contract._transfer(loan.receiver, msg::sender(), loan.amount).is_ok());
+            let result = contract.swap(loan.amount);
+            assert!(matches!(result, Err(Error::ReentrantCall(_))));
+            assert!(contract.transfer(loan.receiver, loan.amount).is_ok());
+        });
+
+        assert!(contract.flash_loan(BORROWER, token, U256::from(500), Bytes::from(vec![])).is_ok());
+        assert_eq!(mock_receiver::flash_loans().len(), 1);
+        assert_eq!(contract::balance(), U256::from(500));
+        assert_eq!(contract.total_supply(), U256::ZERO);
+
+        // The guard is released once the loan is repaid
+        assert!(!contract.entered.get());
+        assert!(!contract.flash_loan_active.get());
+    }
+
+    /// Test that flash loans are blocked while minting is paused.
+    #[motsu::test]
+    fn flash_loan_respects_mint_pause(contract: Erc20Aton) {
+        let token = contract::address();
+        contract.owner.set(OWNER);
+        contract._grant_role(PAUSER_ROLE, msg::sender());
+        assert!(contract.pause(PAUSE_MINT).is_ok());
+        mock_receiver::set_flash_loan_response(Some(FLASH_LOAN_CALLBACK_SUCCESS));
+
+        let result = contract.flash_loan(BORROWER, token, U256::from(100), Bytes::from(vec![]));
+        assert!(matches!(result, Err(Error::EnforcedPause(_))));
+        assert!(mock_receiver::flash_loans().is_empty());
+        assert_eq!(contract.total_supply(), U256::ZERO);
+    }
+
+    /// Test that the loan cannot be taken without approving the repayment.
+    #[motsu::test]
+    fn flash_loan_requires_allowance(contract: Erc20Aton) {
+        let token = contract::address();
+        assert!(helpers::initialize(contract));
+        mock_receiver::set_flash_loan_response(Some(FLASH_LOAN_CALLBACK_SUCCESS));
+
+        let result = contract.flash_loan(BORROWER, token, U256::from(100), Bytes::from(vec![]));
+        assert!(matches!(result, Err(Error::InsufficientAllowance(_))));
+
+        let result = contract.flash_loan(BORROWER, VAULT, U256::from(100), Bytes::from(vec![]));
+        assert!(matches!(result, Err(Error::ExceededMaxLoan(_))));
+    }
+}
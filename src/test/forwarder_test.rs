@@ -0,0 +1,89 @@
+// tests/forwarder_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, BOB};
+    use crate::{Erc20Aton, Error, RELAYED_SENDER};
+    use alloy_sol_types::{sol, SolCall};
+    use stylus_sdk::{
+        alloy_primitives::{Address, U256},
+        msg,
+    };
+
+    sol! {
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+
+    /// Initializes the token, gives `ALICE` 100 ATON and makes
+    /// `msg::sender()` the trusted forwarder.
+    fn setup(contract: &mut Erc20Aton) {
+        assert!(helpers::initialize_with_balance(contract, ALICE, 100));
+        assert!(contract.set_trusted_forwarder(msg::sender()).is_ok());
+    }
+
+    /// Returns `call` with `sender` appended, as the forwarder sends it.
+    fn relayed(call: Vec<u8>, sender: Address) -> Vec<u8> {
+        [call, sender.to_vec()].concat()
+    }
+
+    /// Test that only the owner can set the forwarder and that zero is never
+    /// trusted.
+    #[motsu::test]
+    fn set_trusted_forwarder_is_owner_only(contract: Erc20Aton) {
+        setup(contract);
+        assert_eq!(contract.trusted_forwarder(), msg::sender());
+        assert!(contract.is_trusted_forwarder(msg::sender()));
+        assert!(!contract.is_trusted_forwarder(ALICE));
+
+        assert!(contract.set_trusted_forwarder(Address::ZERO).is_ok());
+        assert!(!contract.is_trusted_forwarder(Address::ZERO));
+
+        // Relaying a non-owner to the owner-only setter is rejected
+        assert!(contract.set_trusted_forwarder(msg::sender()).is_ok());
+        RELAYED_SENDER.set(Some(ALICE));
+        let result = contract.set_trusted_forwarder(BOB);
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+    }
+
+    /// Test that calls from the forwarder are attributed to the relayed
+    /// sender.
+    #[motsu::test]
+    fn relayed_calls_use_appended_sender(contract: Erc20Aton) {
+        setup(contract);
+        RELAYED_SENDER.set(Some(ALICE));
+        assert_eq!(contract._msg_sender(), ALICE);
+
+        assert!(contract.transfer(BOB, U256::from(30)).is_ok());
+        assert!(contract.approve(BOB, U256::from(5)).is_ok());
+        assert_eq!(contract.balance_of(ALICE), U256::from(70));
+        assert_eq!(contract.balance_of(BOB), U256::from(30));
+        assert_eq!(contract.allowance(ALICE, BOB), U256::from(5));
+
+        // Engine checks apply to the relayed sender
+        let result = contract.lock(ALICE, U256::from(1), U256::from(1));
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+
+        // Without a trusted forwarder the appended sender is ignored
+        RELAYED_SENDER.set(None);
+        assert!(contract.set_trusted_forwarder(Address::ZERO).is_ok());
+        RELAYED_SENDER.set(Some(ALICE));
+        assert_eq!(contract._msg_sender(), msg::sender());
+    }
+
+    /// Test that the entrypoint strips the forwarder's suffix before routing.
+    #[motsu::test]
+    fn entrypoint_strips_forwarder_suffix(contract: Erc20Aton) {
+        setup(contract);
+        let call = transferCall { to: BOB, value: U256::from(40) }.abi_encode();
+
+        assert!(crate::user_main(relayed(call.clone(), ALICE)).is_ok());
+        assert_eq!(contract.balance_of(ALICE), U256::from(60));
+        assert_eq!(contract.balance_of(BOB), U256::from(40));
+
+        // Other callers cannot append a sender: the calldata fails to decode
+        RELAYED_SENDER.set(None);
+        assert!(contract.set_trusted_forwarder(Address::ZERO).is_ok());
+        assert!(crate::user_main(relayed(call, ALICE)).is_err());
+        assert_eq!(contract.balance_of(ALICE), U256::from(60));
+    }
+}
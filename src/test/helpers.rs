@@ -0,0 +1,70 @@
+// tests/helpers.rs
+
+//! Setup shared by the token tests.
+
+use crate::Erc20Aton;
+use ethers::{
+    signers::{LocalWallet, Signer},
+    types::H256,
+};
+use stylus_sdk::alloy_primitives::{address, Address, B256, U256};
+
+/// Token name used by [`initialize`].
+pub const NAME: &str = "ATON Stylus";
+/// Token symbol used by [`initialize`].
+pub const SYMBOL: &str = "ATON";
+/// Token decimals used by [`initialize`].
+pub const DECIMALS: u8 = 18;
+
+/// Vault address used by the tests.
+pub const VAULT: Address = address!("7e32b54800705876d3b5cfbc7d9c226a211f7c1a");
+/// Player addresses used by the tests.
+pub const ALICE: Address = address!("00000000000000000000000000000000000a11ce");
+pub const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+/// Owner address distinct from `msg::sender()`.
+pub const OWNER: Address = address!("0000000000000000000000000000000000000a0a");
+
+/// Initializes `contract` with the production metadata, no vault and no
+/// stake engines, making the test sender the owner.
+pub fn initialize(contract: &mut Erc20Aton) -> bool {
+    contract
+        .initialize(NAME.into(), SYMBOL.into(), DECIMALS, Address::ZERO, Vec::new())
+        .is_ok()
+}
+
+/// Initializes `contract` as [`initialize`] does and mints `amount` ATON to
+/// `account`.
+pub fn initialize_with_balance(contract: &mut Erc20Aton, account: Address, amount: u64) -> bool {
+    initialize(contract) && contract._mint(account, U256::from(amount)).is_ok()
+}
+
+/// Initializes `contract` as [`initialize`] does, sets [`VAULT`] as its vault
+/// and mints `vault_balance` ATON to it.
+pub fn initialize_with_vault(contract: &mut Erc20Aton, vault_balance: u64) -> bool {
+    initialize(contract)
+        && contract.set_vault(VAULT).is_ok()
+        && contract._mint(VAULT, U256::from(vault_balance)).is_ok()
+}
+
+/// Private key of the account signing permits and authorizations in tests.
+const SIGNER_KEY: [u8; 32] = [0x5a; 32];
+
+/// Returns the wallet holding [`SIGNER_KEY`].
+fn wallet() -> LocalWallet {
+    LocalWallet::from_bytes(&SIGNER_KEY).unwrap()
+}
+
+/// Returns the address of the test signer.
+pub fn signer() -> Address {
+    Address::from(wallet().address().0)
+}
+
+/// Signs `digest` as the test signer and returns the `(v, r, s)` values the
+/// token expects.
+pub fn sign(digest: B256) -> (u8, B256, B256) {
+    let signature = wallet().sign_hash(H256(digest.0)).unwrap();
+    let (mut r, mut s) = ([0u8; 32], [0u8; 32]);
+    signature.r.to_big_endian(&mut r);
+    signature.s.to_big_endian(&mut s);
+    (signature.v as u8, B256::from(r), B256::from(s))
+}
@@ -0,0 +1,53 @@
+// tests/mock_price_feed.rs
+
+//! Programmable stand-in for the `IPriceFeed` aggregator.
+//!
+//! The token reads `latestRoundData` and `decimals` here in test builds, one
+//! feed per test thread.
+
+use std::cell::RefCell;
+
+use stylus_sdk::alloy_primitives::{Address, I256, U256};
+
+/// State of the mock feed for the current test thread.
+#[derive(Default)]
+struct MockPriceFeed {
+    /// Latest answer reported by the feed.
+    answer: I256,
+    /// Time at which `answer` was reported.
+    updated_at: U256,
+    /// Decimals of `answer`.
+    decimals: u8,
+    /// Whether calls to the feed revert.
+    fail: bool,
+}
+
+thread_local! {
+    static FEED: RefCell<MockPriceFeed> = RefCell::new(MockPriceFeed::default());
+}
+
+/// Sets the latest answer of the feed, its update time and its decimals.
+pub fn set_price(answer: I256, updated_at: U256, decimals: u8) {
+    FEED.with(|feed| {
+        let mut feed = feed.borrow_mut();
+        feed.answer = answer;
+        feed.updated_at = updated_at;
+        feed.decimals = decimals;
+    });
+}
+
+/// Makes every call to the feed revert when `fail` is set.
+pub fn fail(fail: bool) {
+    FEED.with(|feed| feed.borrow_mut().fail = fail);
+}
+
+/// Mock of `IPriceFeed::latestRoundData` followed by `IPriceFeed::decimals`.
+pub fn latest_price(_feed: Address) -> Result<(I256, U256, u8), Vec<u8>> {
+    FEED.with(|feed| {
+        let feed = feed.borrow();
+        if feed.fail {
+            return Err(Vec::new());
+        }
+        Ok((feed.answer, feed.updated_at, feed.decimals))
+    })
+}
@@ -0,0 +1,180 @@
+// tests/mock_receiver.rs
+
+//! Programmable stand-in for contracts the token calls back into.
+//!
+//! The motsu test environment cannot execute other contracts, so in test
+//! builds the token routes its receiver callbacks here. Each callback returns
+//! the response configured for the current test thread.
+
+use std::cell::RefCell;
+
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, B256, U256},
+};
+
+use crate::Erc20Aton;
+
+/// Code run by the mock borrower inside `onFlashLoan`, standing in for the
+/// borrower's calls back into the token.
+pub type FlashLoanHook = fn(&mut Erc20Aton, &FlashLoan);
+
+/// A transfer notified to an ERC-1363 receiver.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferReceived {
+    pub receiver: Address,
+    pub operator: Address,
+    pub from: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+/// An approval notified to an ERC-1363 spender.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApprovalReceived {
+    pub spender: Address,
+    pub owner: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+/// A flash loan received by the mock borrower.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlashLoan {
+    pub receiver: Address,
+    pub initiator: Address,
+    pub token: Address,
+    pub amount: U256,
+    pub fee: U256,
+    pub data: Vec<u8>,
+}
+
+/// State of the mock receivers for the current test thread.
+#[derive(Default)]
+struct MockReceiver {
+    /// Value returned by `onFlashLoan`, or `None` to revert.
+    flash_loan_response: Option<B256>,
+    /// Flash loans received, in call order.
+    flash_loans: Vec<FlashLoan>,
+    /// Code run inside `onFlashLoan`, if any.
+    flash_loan_hook: Option<FlashLoanHook>,
+    /// Value returned by `onTransferReceived`, or `None` to revert.
+    transfer_response: Option<FixedBytes<4>>,
+    /// Transfers notified to receivers, in call order.
+    transfers: Vec<TransferReceived>,
+    /// Value returned by `onApprovalReceived`, or `None` to revert.
+    approval_response: Option<FixedBytes<4>>,
+    /// Approvals notified to spenders, in call order.
+    approvals: Vec<ApprovalReceived>,
+}
+
+thread_local! {
+    static RECEIVER: RefCell<MockReceiver> = RefCell::new(MockReceiver::default());
+}
+
+/// Sets the value returned by `onFlashLoan`, or makes it revert with `None`.
+pub fn set_flash_loan_response(response: Option<B256>) {
+    RECEIVER.with(|receiver| receiver.borrow_mut().flash_loan_response = response);
+}
+
+/// Sets the code the borrower runs inside `onFlashLoan`.
+pub fn set_flash_loan_hook(hook: FlashLoanHook) {
+    RECEIVER.with(|receiver| receiver.borrow_mut().flash_loan_hook = Some(hook));
+}
+
+/// Returns the flash loans received, in call order.
+pub fn flash_loans() -> Vec<FlashLoan> {
+    RECEIVER.with(|receiver| receiver.borrow().flash_loans.clone())
+}
+
+/// Sets the value returned by `onTransferReceived`, or makes it revert with
+/// `None`.
+pub fn set_transfer_response(response: Option<FixedBytes<4>>) {
+    RECEIVER.with(|receiver| receiver.borrow_mut().transfer_response = response);
+}
+
+/// Returns the transfers notified to receivers, in call order.
+pub fn transfers() -> Vec<TransferReceived> {
+    RECEIVER.with(|receiver| receiver.borrow().transfers.clone())
+}
+
+/// Sets the value returned by `onApprovalReceived`, or makes it revert with
+/// `None`.
+pub fn set_approval_response(response: Option<FixedBytes<4>>) {
+    RECEIVER.with(|receiver| receiver.borrow_mut().approval_response = response);
+}
+
+/// Returns the approvals notified to spenders, in call order.
+pub fn approvals() -> Vec<ApprovalReceived> {
+    RECEIVER.with(|receiver| receiver.borrow().approvals.clone())
+}
+
+/// Mock of `IERC1363Receiver::onTransferReceived`.
+pub fn on_transfer_received(
+    receiver: Address,
+    operator: Address,
+    from: Address,
+    value: U256,
+    data: Bytes,
+) -> Result<FixedBytes<4>, Vec<u8>> {
+    RECEIVER.with(|mock| {
+        let mut mock = mock.borrow_mut();
+        mock.transfers.push(TransferReceived {
+            receiver,
+            operator,
+            from,
+            value,
+            data: data.to_vec(),
+        });
+        mock.transfer_response.ok_or_else(Vec::new)
+    })
+}
+
+/// Mock of `IERC1363Spender::onApprovalReceived`.
+pub fn on_approval_received(
+    spender: Address,
+    owner: Address,
+    value: U256,
+    data: Bytes,
+) -> Result<FixedBytes<4>, Vec<u8>> {
+    RECEIVER.with(|mock| {
+        let mut mock = mock.borrow_mut();
+        mock.approvals.push(ApprovalReceived {
+            spender,
+            owner,
+            value,
+            data: data.to_vec(),
+        });
+        mock.approval_response.ok_or_else(Vec::new)
+    })
+}
+
+/// Mock of `IERC3156FlashBorrower::onFlashLoan`, running the configured
+/// hook against `token_contract` before answering.
+pub fn on_flash_loan(
+    token_contract: &mut Erc20Aton,
+    receiver: Address,
+    initiator: Address,
+    token: Address,
+    amount: U256,
+    fee: U256,
+    data: Bytes,
+) -> Result<B256, Vec<u8>> {
+    let loan = FlashLoan {
+        receiver,
+        initiator,
+        token,
+        amount,
+        fee,
+        data: data.to_vec(),
+    };
+    let (hook, response) = RECEIVER.with(|mock| {
+        let mut mock = mock.borrow_mut();
+        mock.flash_loans.push(loan.clone());
+        (mock.flash_loan_hook, mock.flash_loan_response)
+    });
+    if let Some(hook) = hook {
+        hook(token_contract, &loan);
+    }
+    response.ok_or_else(Vec::new)
+}
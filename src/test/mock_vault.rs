@@ -0,0 +1,80 @@
+// tests/mock_vault.rs
+
+//! Programmable stand-in for the `IVault` contract.
+//!
+//! The motsu test environment cannot execute other contracts, so the token
+//! routes its `playerCommission` and `clearCommission` calls here in test
+//! builds. State is kept per test thread, like motsu's own storage.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use stylus_sdk::alloy_primitives::{Address, U256};
+
+/// State of the mock vault for the current test thread.
+#[derive(Default)]
+struct MockVault {
+    /// Commission returned by `playerCommission` for each player.
+    commissions: HashMap<Address, U256>,
+    /// Players whose commission was cleared, in call order.
+    cleared: Vec<Address>,
+    /// Number of `playerCommission` calls made.
+    queries: usize,
+    /// Whether `playerCommission` calls revert.
+    fail_player_commission: bool,
+    /// Whether `clearCommission` calls revert.
+    fail_clear_commission: bool,
+}
+
+thread_local! {
+    static VAULT: RefCell<MockVault> = RefCell::new(MockVault::default());
+}
+
+/// Sets the commission the vault reports for `player`.
+pub fn set_commission(player: Address, amount: U256) {
+    VAULT.with(|vault| vault.borrow_mut().commissions.insert(player, amount));
+}
+
+/// Makes every `playerCommission` call revert when `fail` is set.
+pub fn fail_player_commission(fail: bool) {
+    VAULT.with(|vault| vault.borrow_mut().fail_player_commission = fail);
+}
+
+/// Makes every `clearCommission` call revert when `fail` is set.
+pub fn fail_clear_commission(fail: bool) {
+    VAULT.with(|vault| vault.borrow_mut().fail_clear_commission = fail);
+}
+
+/// Returns the players whose commission was cleared, in call order.
+pub fn cleared() -> Vec<Address> {
+    VAULT.with(|vault| vault.borrow().cleared.clone())
+}
+
+/// Returns the number of `playerCommission` calls made so far.
+pub fn queries() -> usize {
+    VAULT.with(|vault| vault.borrow().queries)
+}
+
+/// Mock of `IVault::playerCommission`.
+pub fn player_commission(_vault: Address, player: Address) -> Result<U256, Vec<u8>> {
+    VAULT.with(|vault| {
+        let mut vault = vault.borrow_mut();
+        vault.queries += 1;
+        if vault.fail_player_commission {
+            return Err(Vec::new());
+        }
+        Ok(vault.commissions.get(&player).copied().unwrap_or_default())
+    })
+}
+
+/// Mock of `IVault::clearCommission`.
+pub fn clear_commission(_vault: Address, player: Address) -> Result<(), Vec<u8>> {
+    VAULT.with(|vault| {
+        let mut vault = vault.borrow_mut();
+        if vault.fail_clear_commission {
+            return Err(Vec::new());
+        }
+        vault.commissions.remove(&player);
+        vault.cleared.push(player);
+        Ok(())
+    })
+}
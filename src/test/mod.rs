@@ -1,2 +1,30 @@
+pub mod authorization_test;
+pub mod batch_payout_test;
+pub mod batch_transfer_test;
+pub mod bet_lock_test;
+// Environment settings for on-chain scripts, unused by the unit tests
+#[allow(dead_code, clippy::empty_line_after_doc_comments)]
 pub mod constants;
+pub mod erc1363_test;
 pub mod erc20aton_test;
+pub mod exchange_rate_test;
+pub mod flash_loan_test;
+pub mod forwarder_test;
+#[cfg(test)]
+pub mod helpers;
+#[cfg(test)]
+pub mod mock_price_feed;
+#[cfg(test)]
+pub mod mock_receiver;
+#[cfg(test)]
+pub mod mock_vault;
+pub mod ownership_test;
+pub mod pause_test;
+pub mod permit_test;
+pub mod roles_test;
+#[cfg(test)]
+pub mod shims;
+pub mod snapshot_test;
+pub mod swap_queue_test;
+pub mod transfer_test;
+pub mod votes_test;
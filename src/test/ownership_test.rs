@@ -0,0 +1,84 @@
+// tests/ownership_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE};
+    use crate::{Erc20Aton, Error, DEFAULT_ADMIN_ROLE, PAUSER_ROLE};
+    use stylus_sdk::{alloy_primitives::Address, msg};
+
+    /// Test that ownership only moves once the new owner accepts it.
+    #[motsu::test]
+    fn transfer_ownership_is_two_step(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let new_owner = ALICE;
+        assert!(helpers::initialize(contract));
+
+        let result = contract.transfer_ownership(Address::ZERO);
+        assert!(matches!(result, Err(Error::Zero(_))));
+
+        assert!(contract.transfer_ownership(new_owner).is_ok());
+        assert_eq!(contract.owner(), sender);
+        assert_eq!(contract.pending_owner(), new_owner);
+
+        // Only the pending owner can accept
+        let result = contract.accept_ownership();
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+
+        assert!(contract.cancel_ownership_transfer().is_ok());
+        assert!(contract.pending_owner().is_zero());
+        assert_eq!(contract.owner(), sender);
+    }
+
+    /// Test that the pending owner becomes the owner on acceptance.
+    #[motsu::test]
+    fn accept_ownership(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let previous_owner = ALICE;
+        contract.owner.set(previous_owner);
+        contract.pending_owner.set(sender);
+
+        assert!(contract.accept_ownership().is_ok());
+        assert_eq!(contract.owner(), sender);
+        assert!(contract.pending_owner().is_zero());
+        assert!(contract.has_role(DEFAULT_ADMIN_ROLE, sender));
+    }
+
+    /// Test that a renounced contract has no owner and cannot be re-initialized.
+    #[motsu::test]
+    fn renounce_ownership(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(helpers::initialize(contract));
+
+        assert!(contract.renounce_ownership().is_ok());
+        assert!(contract.owner().is_zero());
+        assert!(!helpers::initialize(contract));
+
+        let result = contract.update_stake_engine(sender, true);
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+    }
+
+    /// Test that an admin who is not the owner cannot move or renounce
+    /// ownership.
+    #[motsu::test]
+    fn ownership_requires_owner_not_admin(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let owner = ALICE;
+        assert!(helpers::initialize(contract));
+        // The sender stays a co-admin while someone else owns the contract
+        contract.owner.set(owner);
+        contract._grant_role(DEFAULT_ADMIN_ROLE, owner);
+        assert!(contract.has_role(DEFAULT_ADMIN_ROLE, sender));
+
+        let result = contract.transfer_ownership(sender);
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+        let result = contract.cancel_ownership_transfer();
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+        let result = contract.renounce_ownership();
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+        assert_eq!(contract.owner(), owner);
+        assert!(contract.has_role(DEFAULT_ADMIN_ROLE, owner));
+
+        // Role administration stays with every admin
+        assert!(contract.grant_role(PAUSER_ROLE, owner).is_ok());
+    }
+}
@@ -0,0 +1,61 @@
+// tests/pause_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, OWNER};
+    use crate::test::shims;
+    use crate::{Erc20Aton, Error, PAUSER_ROLE, PAUSE_MINT, PAUSE_SWAP, PAUSE_TRANSFERS};
+    use stylus_sdk::{alloy_primitives::U256, contract, msg};
+
+    /// Test that only pausers other than the owner can pause.
+    #[motsu::test]
+    fn pause_requires_pauser_other_than_owner(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(helpers::initialize(contract));
+
+        let result = contract.pause(PAUSE_TRANSFERS);
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+
+        // Holding the role is not enough for the owner
+        assert!(contract.grant_role(PAUSER_ROLE, sender).is_ok());
+        let result = contract.pause(PAUSE_TRANSFERS);
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+        assert_eq!(contract.paused(), 0);
+    }
+
+    /// Test that each pause flag blocks only its own operation.
+    #[motsu::test]
+    fn pause_flags_are_granular(contract: Erc20Aton) {
+        let sender = msg::sender();
+        contract.owner.set(OWNER);
+        contract._grant_role(PAUSER_ROLE, sender);
+        assert!(contract._mint(sender, U256::from(50)).is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(50));
+
+        assert!(contract.pause(PAUSE_SWAP | PAUSE_MINT).is_ok());
+        assert_eq!(contract.paused(), PAUSE_SWAP | PAUSE_MINT);
+
+        let result = contract.swap(U256::from(1));
+        assert!(matches!(result, Err(Error::EnforcedPause(_))));
+        let result = contract._engine_mint(sender, U256::from(1));
+        assert!(matches!(result, Err(Error::EnforcedPause(_))));
+        assert!(contract.transfer(ALICE, U256::from(10)).is_ok());
+
+        assert!(contract.pause(PAUSE_TRANSFERS).is_ok());
+        let result = contract.transfer(ALICE, U256::from(10));
+        assert!(matches!(result, Err(Error::EnforcedPause(_))));
+
+        // Unknown bits are rejected rather than stored
+        let result = contract.pause(1 << 3);
+        assert!(matches!(result, Err(Error::InvalidPauseFlags(_))));
+        let result = contract.unpause(PAUSE_MINT | 0x80);
+        assert!(matches!(result, Err(Error::InvalidPauseFlags(_))));
+        assert_eq!(contract.paused(), PAUSE_SWAP | PAUSE_MINT | PAUSE_TRANSFERS);
+
+        assert!(contract.unpause(PAUSE_TRANSFERS | PAUSE_SWAP).is_ok());
+        assert_eq!(contract.paused(), PAUSE_MINT);
+        assert!(contract.transfer(ALICE, U256::from(10)).is_ok());
+        assert!(contract.swap(U256::from(5)).is_ok());
+        assert_eq!(contract.balance_of(ALICE), U256::from(20));
+    }
+}
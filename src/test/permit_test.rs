@@ -0,0 +1,97 @@
+// tests/permit_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, BOB};
+    use crate::{Erc20Aton, Error};
+    use stylus_sdk::{
+        alloy_primitives::{B256, U256},
+        block, msg,
+    };
+
+    /// Test that a permit signed by the owner sets the allowance and
+    /// consumes the owner's nonce.
+    #[motsu::test]
+    fn permit_accepts_owner_signature(contract: Erc20Aton) {
+        let owner = helpers::signer();
+        let spender = BOB;
+        let value = U256::from(1_000);
+        let deadline = U256::from(block::timestamp() + 3_600);
+
+        let digest = contract._permit_digest(owner, spender, value, U256::ZERO, deadline);
+        let (v, r, s) = helpers::sign(digest);
+        assert!(contract.permit(owner, spender, value, deadline, v, r, s).is_ok());
+        assert_eq!(contract.allowance(owner, spender), value);
+        assert_eq!(contract.nonces(owner), U256::from(1));
+
+        // The signature does not verify for another owner or value
+        let result = contract.permit(spender, spender, value, deadline, v, r, s);
+        assert!(matches!(result, Err(Error::InvalidSigner(_))));
+        let result = contract.permit(owner, spender, U256::from(1), deadline, v, r, s);
+        assert!(matches!(result, Err(Error::InvalidSigner(_))));
+        assert_eq!(contract.allowance(owner, spender), value);
+    }
+
+    /// Test that a signature cannot be replayed once its nonce is consumed.
+    #[motsu::test]
+    fn permit_rejects_replay(contract: Erc20Aton) {
+        let owner = helpers::signer();
+        let spender = BOB;
+        let value = U256::from(1_000);
+        let deadline = U256::from(block::timestamp() + 3_600);
+
+        let digest = contract._permit_digest(owner, spender, value, U256::ZERO, deadline);
+        let (v, r, s) = helpers::sign(digest);
+        assert!(contract.permit(owner, spender, value, deadline, v, r, s).is_ok());
+
+        // The spender uses part of the allowance, then replays the permit
+        assert!(contract._spend_allowance(owner, spender, U256::from(400)).is_ok());
+        let result = contract.permit(owner, spender, value, deadline, v, r, s);
+        assert!(matches!(result, Err(Error::InvalidSigner(_))));
+        assert_eq!(contract.allowance(owner, spender), U256::from(600));
+    }
+
+    /// Test that a validly signed permit submitted after its deadline is
+    /// rejected without consuming the nonce.
+    #[motsu::test]
+    fn permit_rejects_expired_deadline(contract: Erc20Aton) {
+        let owner = helpers::signer();
+        let spender = BOB;
+        let deadline = U256::from(block::timestamp() - 1);
+
+        let digest = contract._permit_digest(owner, spender, U256::from(1), U256::ZERO, deadline);
+        let (v, r, s) = helpers::sign(digest);
+        let result = contract.permit(owner, spender, U256::from(1), deadline, v, r, s);
+        assert!(matches!(result, Err(Error::ExpiredSignature(_))));
+
+        assert_eq!(contract.nonces(owner), U256::ZERO);
+        assert_eq!(contract.allowance(owner, spender), U256::ZERO);
+    }
+
+    /// Test that signatures with a malleable `s` value are rejected.
+    #[motsu::test]
+    fn permit_rejects_malleable_signature(contract: Erc20Aton) {
+        let owner = msg::sender();
+        let spender = BOB;
+        let deadline = U256::from(block::timestamp() + 3_600);
+
+        let result = contract.permit(
+            owner,
+            spender,
+            U256::from(1),
+            deadline,
+            27,
+            B256::repeat_byte(1),
+            B256::repeat_byte(0xff),
+        );
+        assert!(matches!(result, Err(Error::InvalidSignatureS(_))));
+    }
+
+    /// Test that the domain separator is bound to the token name and address.
+    #[motsu::test]
+    fn domain_separator(contract: Erc20Aton) {
+        let separator = contract.domain_separator();
+        assert_ne!(separator, B256::ZERO);
+        assert_eq!(separator, contract.domain_separator());
+    }
+}
@@ -0,0 +1,77 @@
+// tests/roles_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE};
+    use crate::{Erc20Aton, Error, DEFAULT_ADMIN_ROLE, ENGINE_ROLE, MINTER_ROLE, PAUSER_ROLE};
+    use stylus_sdk::msg;
+
+    /// Test that the admin can grant and revoke roles.
+    #[motsu::test]
+    fn grant_and_revoke_role(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let pauser = ALICE;
+
+        // Nobody administers roles before initialization
+        let result = contract.grant_role(PAUSER_ROLE, pauser);
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+
+        assert!(helpers::initialize(contract));
+        assert!(contract.has_role(DEFAULT_ADMIN_ROLE, sender));
+
+        assert!(contract.grant_role(PAUSER_ROLE, pauser).is_ok());
+        assert!(contract.has_role(PAUSER_ROLE, pauser));
+        assert!(!contract.has_role(PAUSER_ROLE, sender));
+
+        assert!(contract.revoke_role(PAUSER_ROLE, pauser).is_ok());
+        assert!(!contract.has_role(PAUSER_ROLE, pauser));
+    }
+
+    /// Test that stake engines are tracked through `ENGINE_ROLE`.
+    #[motsu::test]
+    fn stake_engine_uses_engine_role(contract: Erc20Aton) {
+        let engine = ALICE;
+        assert!(helpers::initialize(contract));
+
+        assert!(contract.update_stake_engine(engine, true).is_ok());
+        assert!(contract.has_role(ENGINE_ROLE, engine));
+        assert!(contract.is_stake_engine(engine));
+
+        assert!(contract.revoke_role(ENGINE_ROLE, engine).is_ok());
+        assert!(!contract.is_stake_engine(engine));
+    }
+
+    /// Test that accounts can only renounce their own roles.
+    #[motsu::test]
+    fn renounce_role(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let other = ALICE;
+        assert!(helpers::initialize(contract));
+        assert!(contract.grant_role(MINTER_ROLE, sender).is_ok());
+
+        let result = contract.renounce_role(MINTER_ROLE, other);
+        assert!(matches!(result, Err(Error::AccessControlBadConfirmation(_))));
+
+        assert!(contract.renounce_role(MINTER_ROLE, sender).is_ok());
+        assert!(!contract.has_role(MINTER_ROLE, sender));
+    }
+
+    /// Test that a role's admin role controls who may grant it.
+    #[motsu::test]
+    fn set_role_admin(contract: Erc20Aton) {
+        let sender = msg::sender();
+        let engine = ALICE;
+        assert!(helpers::initialize(contract));
+
+        assert!(contract.set_role_admin(ENGINE_ROLE, MINTER_ROLE).is_ok());
+        assert_eq!(contract.get_role_admin(ENGINE_ROLE), MINTER_ROLE);
+
+        // The default admin no longer administers engines
+        let result = contract.update_stake_engine(engine, true);
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+
+        assert!(contract.grant_role(MINTER_ROLE, sender).is_ok());
+        assert!(contract.update_stake_engine(engine, true).is_ok());
+        assert!(contract.is_stake_engine(engine));
+    }
+}
@@ -0,0 +1,106 @@
+// tests/shims.rs
+
+//! Host functions missing from the motsu test environment.
+//!
+//! motsu only mocks the hostios its own contracts need. These shims add the
+//! ones used by `mint_aton`, `swap`, the voting checkpoints and the
+//! `reentrant` storage cache, backed by per-thread values that the tests can
+//! program. motsu's `call_contract` always succeeds, so in test
+//! builds `src/calls.rs` routes ETH payouts through [`transfer_eth`] instead.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use stylus_sdk::alloy_primitives::{Address, U256};
+
+thread_local! {
+    static MSG_VALUE: RefCell<U256> = const { RefCell::new(U256::ZERO) };
+    static BALANCES: RefCell<HashMap<Address, U256>> = RefCell::new(HashMap::new());
+    static FAIL_ETH_TRANSFERS: RefCell<bool> = const { RefCell::new(false) };
+    static BLOCK_NUMBER: RefCell<u64> = const { RefCell::new(1) };
+}
+
+/// Sets the ETH value sent with the next calls.
+pub fn set_msg_value(value: U256) {
+    MSG_VALUE.with(|msg_value| *msg_value.borrow_mut() = value);
+}
+
+/// Sends `value` wei with the next calls, crediting it to the token as the
+/// EVM does before the call runs.
+pub fn send_eth(value: U256) {
+    set_msg_value(value);
+    let token = stylus_sdk::contract::address();
+    BALANCES.with(|balances| *balances.borrow_mut().entry(token).or_default() += value);
+}
+
+/// Sets the ETH balance of `account`.
+pub fn set_eth_balance(account: Address, balance: U256) {
+    BALANCES.with(|balances| balances.borrow_mut().insert(account, balance));
+}
+
+/// Makes every ETH payout fail when `fail` is set.
+pub fn fail_eth_transfers(fail: bool) {
+    FAIL_ETH_TRANSFERS.with(|flag| *flag.borrow_mut() = fail);
+}
+
+/// Sets the number of the current block.
+pub fn set_block_number(number: u64) {
+    BLOCK_NUMBER.with(|block_number| *block_number.borrow_mut() = number);
+}
+
+/// Mock of `stylus_sdk::call::transfer_eth`, moving balances between the
+/// accounts tracked by [`account_balance`].
+pub fn transfer_eth(to: Address, amount: U256) -> Result<(), Vec<u8>> {
+    if FAIL_ETH_TRANSFERS.with(|flag| *flag.borrow()) {
+        return Err(Vec::new());
+    }
+
+    let from = stylus_sdk::contract::address();
+    BALANCES.with(|balances| {
+        let mut balances = balances.borrow_mut();
+        let balance = balances.get(&from).copied().unwrap_or_default();
+        balances.insert(from, balance.saturating_sub(amount));
+        *balances.entry(to).or_default() += amount;
+    });
+    Ok(())
+}
+
+/// Gets the ETH value sent with the current call.
+///
+/// # Safety
+///
+/// `value` must point to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn msg_value(value: *mut u8) {
+    let bytes = MSG_VALUE.with(|msg_value| msg_value.borrow().to_be_bytes::<32>());
+    std::ptr::copy(bytes.as_ptr(), value, 32);
+}
+
+/// Gets the number of the current block.
+#[no_mangle]
+pub extern "C" fn block_number() -> u64 {
+    BLOCK_NUMBER.with(|block_number| *block_number.borrow())
+}
+
+/// Persists the storage cache. motsu writes storage through on every
+/// store, so there is nothing left to flush.
+#[no_mangle]
+pub extern "C" fn storage_flush_cache(_clear: bool) {}
+
+/// Gets the ETH balance of the account at the given address.
+///
+/// # Safety
+///
+/// `address` must point to 20 readable bytes and `dest` to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn account_balance(address: *const u8, dest: *mut u8) {
+    let account = Address::from_slice(std::slice::from_raw_parts(address, 20));
+    let bytes = BALANCES.with(|balances| {
+        balances
+            .borrow()
+            .get(&account)
+            .copied()
+            .unwrap_or_default()
+            .to_be_bytes::<32>()
+    });
+    std::ptr::copy(bytes.as_ptr(), dest, 32);
+}
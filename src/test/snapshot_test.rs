@@ -0,0 +1,85 @@
+// tests/snapshot_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, BOB};
+    use crate::{Erc20Aton, Error, DEFAULT_ADMIN_ROLE, ENGINE_ROLE};
+    use stylus_sdk::{
+        alloy_primitives::U256,
+        msg,
+    };
+
+    /// Test that only stake engines and the owner can take snapshots.
+    #[motsu::test]
+    fn snapshot_requires_engine_or_owner(contract: Erc20Aton) {
+        let result = contract.snapshot();
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+
+        assert!(helpers::initialize(contract));
+        assert_eq!(contract.snapshot().ok(), Some(U256::from(1)));
+
+        // An admin who is not the owner cannot snapshot
+        contract.owner.set(ALICE);
+        assert!(contract.has_role(DEFAULT_ADMIN_ROLE, msg::sender()));
+        let result = contract.snapshot();
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+
+        // An engine that is not the owner can snapshot
+        contract._grant_role(ENGINE_ROLE, msg::sender());
+        assert_eq!(contract.snapshot().ok(), Some(U256::from(2)));
+        assert_eq!(contract.current_snapshot_id(), U256::from(2));
+    }
+
+    /// Test that balances and supply are read as of each snapshot.
+    #[motsu::test]
+    fn balances_at_snapshot(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(helpers::initialize_with_balance(contract, holder, 100));
+
+        let first = contract.snapshot().ok().unwrap();
+        assert!(contract.transfer(ALICE, U256::from(40)).is_ok());
+        assert!(contract._mint(BOB, U256::from(10)).is_ok());
+
+        let second = contract.snapshot().ok().unwrap();
+        assert!(contract.transfer(ALICE, U256::from(10)).is_ok());
+
+        assert_eq!(contract.balance_of_at(holder, first).ok(), Some(U256::from(100)));
+        assert_eq!(contract.balance_of_at(ALICE, first).ok(), Some(U256::ZERO));
+        assert_eq!(contract.balance_of_at(BOB, first).ok(), Some(U256::ZERO));
+        assert_eq!(contract.total_supply_at(first).ok(), Some(U256::from(100)));
+
+        assert_eq!(contract.balance_of_at(holder, second).ok(), Some(U256::from(60)));
+        assert_eq!(contract.balance_of_at(ALICE, second).ok(), Some(U256::from(40)));
+        // Untouched since the second snapshot, so the current balance applies
+        assert_eq!(contract.balance_of_at(BOB, second).ok(), Some(U256::from(10)));
+        assert_eq!(contract.total_supply_at(second).ok(), Some(U256::from(110)));
+    }
+
+    /// Test that each account is written at most once per snapshot.
+    #[motsu::test]
+    fn snapshots_are_written_lazily(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(helpers::initialize_with_balance(contract, holder, 100));
+        assert_eq!(contract.account_snapshots.get(holder).len(), 0);
+
+        let id = contract.snapshot().ok().unwrap();
+        assert!(contract.transfer(ALICE, U256::from(1)).is_ok());
+        assert!(contract.transfer(ALICE, U256::from(1)).is_ok());
+
+        assert_eq!(contract.account_snapshots.get(holder).len(), 1);
+        assert_eq!(contract.account_snapshots.get(ALICE).len(), 1);
+        assert_eq!(contract.balance_of_at(holder, id).ok(), Some(U256::from(100)));
+    }
+
+    /// Test that unknown snapshot ids are rejected.
+    #[motsu::test]
+    fn snapshot_rejects_invalid_id(contract: Erc20Aton) {
+        let result = contract.balance_of_at(ALICE, U256::ZERO);
+        assert!(matches!(result, Err(Error::InvalidSnapshotId(_))));
+
+        assert!(helpers::initialize(contract));
+        let id = contract.snapshot().ok().unwrap();
+        let result = contract.total_supply_at(id + U256::from(1));
+        assert!(matches!(result, Err(Error::InvalidSnapshotId(_))));
+    }
+}
@@ -0,0 +1,144 @@
+// tests/swap_queue_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE};
+    use crate::test::shims;
+    use crate::{Erc20Aton, Error, ENGINE_ROLE, TICKET_CANCELED, TICKET_FULFILLED, TICKET_PENDING};
+    use stylus_sdk::{
+        alloy_primitives::U256,
+        contract, msg,
+    };
+
+    /// Test that a request escrows ATON and issues sequential tickets.
+    #[motsu::test]
+    fn request_swap_escrows_aton(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(helpers::initialize_with_balance(contract, holder, 100));
+
+        assert_eq!(contract.request_swap(U256::from(30)).ok(), Some(U256::from(1)));
+        assert_eq!(contract.request_swap(U256::from(20)).ok(), Some(U256::from(2)));
+
+        assert_eq!(contract.balance_of(holder), U256::from(50));
+        assert_eq!(contract.balance_of(contract::address()), U256::from(50));
+        assert_eq!(contract.queued_swap_amount(), U256::from(50));
+        assert_eq!(contract.swap_ticket(U256::from(1)), (holder, U256::from(30), TICKET_PENDING));
+        assert_eq!(contract.swap_queue(), (U256::from(2), U256::ZERO));
+
+        let result = contract.request_swap(U256::ZERO);
+        assert!(matches!(result, Err(Error::ZeroSwapAmount(_))));
+        let result = contract.request_swap(U256::from(51));
+        assert!(matches!(result, Err(Error::InsufficientSwapBalance(_))));
+    }
+
+    /// Test that tickets are paid in FIFO order as liquidity arrives.
+    #[motsu::test]
+    fn fulfill_swaps_in_fifo_order(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(helpers::initialize_with_balance(contract, holder, 100));
+        assert!(contract.request_swap(U256::from(30)).is_ok());
+        assert!(contract.request_swap(U256::from(20)).is_ok());
+
+        // Only the first ticket can be paid; the second one waits
+        shims::set_eth_balance(contract::address(), U256::from(40));
+        assert_eq!(contract.fulfill_swaps(U256::from(10)).ok(), Some(U256::from(1)));
+        assert_eq!(contract.swap_ticket(U256::from(1)).2, TICKET_FULFILLED);
+        assert_eq!(contract.swap_ticket(U256::from(2)).2, TICKET_PENDING);
+        assert_eq!(contract.swap_queue(), (U256::from(2), U256::from(1)));
+        assert_eq!(contract.total_supply(), U256::from(70));
+        assert_eq!(contract::balance(), U256::from(10));
+
+        // A refill lets the queue drain
+        shims::set_eth_balance(contract::address(), U256::from(20));
+        assert_eq!(contract.fulfill_swaps(U256::from(10)).ok(), Some(U256::from(1)));
+        assert_eq!(contract.queued_swap_amount(), U256::ZERO);
+        assert_eq!(contract.balance_of(contract::address()), U256::ZERO);
+        assert_eq!(contract.reserve_totals().1, U256::from(50));
+    }
+
+    /// Test that engine liquidity deposits drain the queue.
+    #[motsu::test]
+    fn deposit_liquidity_drains_queue(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(helpers::initialize_with_balance(contract, holder, 100));
+        assert!(contract.request_swap(U256::from(30)).is_ok());
+        assert!(contract.request_swap(U256::from(20)).is_ok());
+        assert_eq!(contract.fulfill_swaps(U256::from(10)).ok(), Some(U256::ZERO));
+
+        // Only stake engines deposit liquidity
+        shims::send_eth(U256::from(50));
+        let result = contract.deposit_liquidity();
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+
+        // The reverted call keeps none of the ETH
+        shims::set_eth_balance(contract::address(), U256::ZERO);
+        assert!(contract.grant_role(ENGINE_ROLE, holder).is_ok());
+        shims::send_eth(U256::from(50));
+        assert!(contract.deposit_liquidity().is_ok());
+        assert_eq!(contract.reserve_totals().0, U256::from(50));
+        assert_eq!(contract.total_supply(), U256::from(100));
+
+        assert_eq!(contract.fulfill_swaps(U256::from(10)).ok(), Some(U256::from(2)));
+        assert_eq!(contract.queued_swap_amount(), U256::ZERO);
+        assert_eq!(contract.reserve_totals().1, U256::from(50));
+        assert_eq!(contract::balance(), U256::ZERO);
+    }
+
+    /// Test that direct swaps cannot use ETH owed to queued tickets.
+    #[motsu::test]
+    fn swap_cannot_jump_the_queue(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(helpers::initialize_with_balance(contract, holder, 100));
+        assert!(contract.request_swap(U256::from(30)).is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(40));
+
+        let result = contract.swap(U256::from(11));
+        assert!(matches!(result, Err(Error::InsufficientLiquidity(_))));
+        assert!(contract.swap(U256::from(10)).is_ok());
+    }
+
+    /// Test that canceling refunds the escrow and the queue skips the ticket.
+    #[motsu::test]
+    fn cancel_swap_refunds_escrow(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(helpers::initialize_with_balance(contract, holder, 100));
+        assert!(contract.request_swap(U256::from(30)).is_ok());
+        assert!(contract.request_swap(U256::from(20)).is_ok());
+
+        assert!(contract.cancel_swap(U256::from(1)).is_ok());
+        assert_eq!(contract.balance_of(holder), U256::from(80));
+        assert_eq!(contract.queued_swap_amount(), U256::from(20));
+        assert_eq!(contract.swap_ticket(U256::from(1)).2, TICKET_CANCELED);
+
+        let result = contract.cancel_swap(U256::from(1));
+        assert!(matches!(result, Err(Error::SwapTicketNotPending(_))));
+        let result = contract.cancel_swap(U256::from(3));
+        assert!(matches!(result, Err(Error::SwapTicketNotPending(_))));
+
+        // Tickets of other accounts cannot be canceled
+        contract.swap_tickets.setter(U256::from(2)).owner.set(ALICE);
+        let result = contract.cancel_swap(U256::from(2));
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+
+        shims::set_eth_balance(contract::address(), U256::from(20));
+        assert_eq!(contract.fulfill_swaps(U256::from(10)).ok(), Some(U256::from(1)));
+        assert_eq!(contract.swap_queue(), (U256::from(2), U256::from(2)));
+    }
+
+    /// Test that a failed payout refunds the ticket instead of blocking the
+    /// queue.
+    #[motsu::test]
+    fn failed_payout_refunds_ticket(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(helpers::initialize_with_balance(contract, holder, 100));
+        assert!(contract.request_swap(U256::from(30)).is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(30));
+        shims::fail_eth_transfers(true);
+
+        assert_eq!(contract.fulfill_swaps(U256::from(1)).ok(), Some(U256::ZERO));
+        assert_eq!(contract.swap_ticket(U256::from(1)).2, TICKET_CANCELED);
+        assert_eq!(contract.balance_of(holder), U256::from(100));
+        assert_eq!(contract.queued_swap_amount(), U256::ZERO);
+        assert_eq!(contract::balance(), U256::from(30));
+    }
+}
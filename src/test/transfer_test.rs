@@ -0,0 +1,257 @@
+// tests/transfer_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, BOB, OWNER, VAULT};
+    use crate::test::{mock_vault, shims};
+    use crate::{Erc20Aton, Error, MINTER_ROLE, VAULT_MANAGER_ROLE};
+    use stylus_sdk::{
+        alloy_primitives::{Address, U256},
+        contract, msg,
+    };
+
+    /// Initializes the token with a vault holding `vault_balance` tokens and
+    /// gives `msg::sender()` a balance of `sender_balance`.
+    fn setup(contract: &mut Erc20Aton, vault_balance: u64, sender_balance: u64) {
+        assert!(helpers::initialize_with_vault(contract, vault_balance));
+        assert!(contract._mint(msg::sender(), U256::from(sender_balance)).is_ok());
+    }
+
+    /// Test that a transfer pays out and clears both parties' commissions.
+    #[motsu::test]
+    fn transfer_pays_sender_and_receiver_commission(contract: Erc20Aton) {
+        let sender = msg::sender();
+        setup(contract, 100, 50);
+        mock_vault::set_commission(sender, U256::from(5));
+        mock_vault::set_commission(ALICE, U256::from(3));
+
+        assert!(contract.transfer(ALICE, U256::from(10)).is_ok());
+
+        assert_eq!(contract.balance_of(ALICE), U256::from(13));
+        assert_eq!(contract.balance_of(sender), U256::from(45));
+        assert_eq!(contract.balance_of(VAULT), U256::from(92));
+        assert_eq!(mock_vault::cleared(), vec![ALICE, sender]);
+        assert_eq!(contract.total_supply(), U256::from(150));
+    }
+
+    /// Test that the owner's commission is paid when the contract is a party.
+    #[motsu::test]
+    fn transfer_to_contract_pays_owner_commission(contract: Erc20Aton) {
+        let sender = msg::sender();
+        setup(contract, 100, 50);
+        contract.owner.set(OWNER);
+        mock_vault::set_commission(OWNER, U256::from(7));
+        mock_vault::set_commission(sender, U256::from(2));
+
+        assert!(contract.transfer(contract::address(), U256::from(10)).is_ok());
+
+        assert_eq!(contract.balance_of(OWNER), U256::from(7));
+        assert_eq!(contract.balance_of(sender), U256::from(42));
+        assert_eq!(contract.balance_of(contract::address()), U256::from(10));
+        assert_eq!(contract.balance_of(VAULT), U256::from(91));
+        assert!(mock_vault::cleared().contains(&OWNER));
+    }
+
+    /// Test that the owner is not paid when the contract is not involved.
+    #[motsu::test]
+    fn transfer_between_players_skips_owner_commission(contract: Erc20Aton) {
+        setup(contract, 100, 50);
+        contract.owner.set(OWNER);
+        mock_vault::set_commission(OWNER, U256::from(7));
+
+        assert!(contract.transfer(ALICE, U256::from(10)).is_ok());
+
+        assert_eq!(contract.balance_of(OWNER), U256::ZERO);
+        assert_eq!(contract.balance_of(VAULT), U256::from(100));
+    }
+
+    /// Test that `transfer_from` spends the allowance and settles commissions.
+    #[motsu::test]
+    fn transfer_from_spends_allowance(contract: Erc20Aton) {
+        let spender = msg::sender();
+        setup(contract, 100, 0);
+        assert!(contract._mint(ALICE, U256::from(50)).is_ok());
+        assert!(contract._approve(ALICE, spender, U256::from(30), true).is_ok());
+        mock_vault::set_commission(ALICE, U256::from(4));
+        mock_vault::set_commission(BOB, U256::from(1));
+
+        assert!(contract.transfer_from(ALICE, BOB, U256::from(20)).is_ok());
+
+        assert_eq!(contract.allowance(ALICE, spender), U256::from(10));
+        assert_eq!(contract.balance_of(ALICE), U256::from(34));
+        assert_eq!(contract.balance_of(BOB), U256::from(21));
+        assert_eq!(contract.balance_of(VAULT), U256::from(95));
+
+        let result = contract.transfer_from(ALICE, BOB, U256::from(11));
+        assert!(matches!(result, Err(Error::InsufficientAllowance(_))));
+        assert_eq!(contract.allowance(ALICE, spender), U256::from(10));
+        assert_eq!(contract.balance_of(BOB), U256::from(21));
+    }
+
+    /// Test that an allowance of `U256::MAX` is infinite in `transfer_from`.
+    #[motsu::test]
+    fn transfer_from_keeps_infinite_allowance(contract: Erc20Aton) {
+        let spender = msg::sender();
+        setup(contract, 100, 0);
+        assert!(contract._mint(ALICE, U256::from(50)).is_ok());
+        assert!(contract._approve(ALICE, spender, U256::MAX, true).is_ok());
+
+        assert!(contract.transfer_from(ALICE, BOB, U256::from(20)).is_ok());
+        assert!(contract.transfer_from(ALICE, BOB, U256::from(30)).is_ok());
+        assert_eq!(contract.allowance(ALICE, spender), U256::MAX);
+        assert_eq!(contract.balance_of(BOB), U256::from(50));
+    }
+
+    /// Test that allowances can be raised and lowered relative to their
+    /// current value.
+    #[motsu::test]
+    fn increase_and_decrease_allowance(contract: Erc20Aton) {
+        let owner = msg::sender();
+        setup(contract, 100, 0);
+
+        assert!(contract.increase_allowance(ALICE, U256::from(30)).is_ok());
+        assert!(contract.increase_allowance(ALICE, U256::from(20)).is_ok());
+        assert_eq!(contract.allowance(owner, ALICE), U256::from(50));
+
+        assert!(contract.decrease_allowance(ALICE, U256::from(45)).is_ok());
+        assert_eq!(contract.allowance(owner, ALICE), U256::from(5));
+
+        let result = contract.decrease_allowance(ALICE, U256::from(6));
+        assert!(matches!(result, Err(Error::FailedDecreaseAllowance(_))));
+        assert_eq!(contract.allowance(owner, ALICE), U256::from(5));
+
+        // Increases saturate at the infinite allowance
+        assert!(contract.increase_allowance(ALICE, U256::MAX).is_ok());
+        assert_eq!(contract.allowance(owner, ALICE), U256::MAX);
+
+        let result = contract.increase_allowance(Address::ZERO, U256::from(1));
+        assert!(matches!(result, Err(Error::InvalidSpender(_))));
+    }
+
+    /// Test that a transfer exceeding the balance is rejected.
+    #[motsu::test]
+    fn transfer_rejects_insufficient_balance(contract: Erc20Aton) {
+        let sender = msg::sender();
+        setup(contract, 100, 5);
+
+        let result = contract.transfer(ALICE, U256::from(6));
+        assert!(matches!(result, Err(Error::InsufficientBalance(_))));
+        assert_eq!(contract.balance_of(sender), U256::from(5));
+        assert_eq!(contract.balance_of(ALICE), U256::ZERO);
+    }
+
+    /// Test that strict settlement rejects commissions the vault cannot cover.
+    #[motsu::test]
+    fn strict_settlement_rejects_underfunded_vault(contract: Erc20Aton) {
+        setup(contract, 5, 50);
+        assert!(contract.grant_role(VAULT_MANAGER_ROLE, msg::sender()).is_ok());
+        assert!(contract.set_strict_settlement(true).is_ok());
+        mock_vault::set_commission(ALICE, U256::from(6));
+
+        let result = contract.transfer(ALICE, U256::from(10));
+        assert!(matches!(result, Err(Error::VaultUnderfunded(_))));
+        assert!(mock_vault::cleared().is_empty());
+    }
+
+    /// Test that strict settlement reverts when clearing a commission fails.
+    #[motsu::test]
+    fn strict_settlement_rejects_failed_clear(contract: Erc20Aton) {
+        setup(contract, 100, 50);
+        assert!(contract.grant_role(VAULT_MANAGER_ROLE, msg::sender()).is_ok());
+        assert!(contract.set_strict_settlement(true).is_ok());
+        mock_vault::set_commission(ALICE, U256::from(6));
+        mock_vault::fail_clear_commission(true);
+
+        let result = contract.transfer(ALICE, U256::from(10));
+        assert!(matches!(result, Err(Error::VaultCallFailed(_))));
+
+        // Lenient mode skips the failure
+        assert!(contract.set_strict_settlement(false).is_ok());
+        assert!(contract.transfer(BOB, U256::from(10)).is_ok());
+        assert_eq!(contract.balance_of(BOB), U256::from(10));
+    }
+
+    /// Test that `mint_aton` mints the ETH sent by a stake engine.
+    #[motsu::test]
+    fn mint_aton(contract: Erc20Aton) {
+        let engine = msg::sender();
+        assert!(helpers::initialize(contract));
+        shims::set_msg_value(U256::from(25));
+
+        let result = contract.mint_aton();
+        assert!(matches!(result, Err(Error::AccessControlUnauthorizedAccount(_))));
+
+        assert!(contract.update_stake_engine(engine, true).is_ok());
+        assert!(contract.grant_role(MINTER_ROLE, engine).is_ok());
+        assert!(contract.set_mint_quota(engine, U256::from(25)).is_ok());
+        assert!(contract.mint_aton().is_ok());
+
+        assert_eq!(contract.balance_of(engine), U256::from(25));
+        assert_eq!(contract.total_supply(), U256::from(25));
+        assert_eq!(contract.remaining_mint_quota(engine), U256::ZERO);
+    }
+
+    /// Test that `swap` burns ATON when the contract holds enough ETH.
+    #[motsu::test]
+    fn swap(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(contract._mint(sender, U256::from(40)).is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(30));
+
+        assert!(contract.swap(U256::from(30)).is_ok());
+        assert_eq!(contract.balance_of(sender), U256::from(10));
+        assert_eq!(contract.total_supply(), U256::from(10));
+        assert_eq!(contract::balance(), U256::ZERO);
+    }
+
+    /// Test that each swap failure reports its own error.
+    #[motsu::test]
+    fn swap_rejects_invalid_requests(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(contract._mint(sender, U256::from(40)).is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(30));
+
+        let result = contract.swap(U256::ZERO);
+        assert!(matches!(result, Err(Error::ZeroSwapAmount(_))));
+
+        let result = contract.swap(U256::from(41));
+        assert!(matches!(result, Err(Error::InsufficientSwapBalance(_))));
+
+        let result = contract.swap(U256::from(31));
+        assert!(matches!(result, Err(Error::InsufficientLiquidity(_))));
+
+        assert_eq!(contract.balance_of(sender), U256::from(40));
+    }
+
+    /// Test that a failed ETH payout reverts the swap.
+    #[motsu::test]
+    fn swap_reverts_on_failed_payout(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(contract._mint(sender, U256::from(40)).is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(30));
+        shims::fail_eth_transfers(true);
+
+        let result = contract.swap(U256::from(10));
+        assert!(matches!(result, Err(Error::EthTransferFailed(_))));
+    }
+
+    /// Test that guarded entrypoints reject reentrant calls.
+    #[motsu::test]
+    fn swap_and_mint_are_non_reentrant(contract: Erc20Aton) {
+        let sender = msg::sender();
+        assert!(contract._mint(sender, U256::from(40)).is_ok());
+        shims::set_eth_balance(contract::address(), U256::from(30));
+
+        // Simulate a call made while another guarded call is running
+        assert!(contract._non_reentrant_enter().is_ok());
+        let result = contract.swap(U256::from(10));
+        assert!(matches!(result, Err(Error::ReentrantCall(_))));
+        let result = contract.mint_aton();
+        assert!(matches!(result, Err(Error::ReentrantCall(_))));
+        let result = contract._pay_commissions(ALICE, sender);
+        assert!(matches!(result, Err(Error::ReentrantCall(_))));
+
+        contract._non_reentrant_exit();
+        assert!(contract.swap(U256::from(10)).is_ok());
+    }
+}
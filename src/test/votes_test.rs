@@ -0,0 +1,140 @@
+// tests/votes_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::test::helpers::{self, ALICE, BOB};
+    use crate::test::shims;
+    use crate::{Erc20Aton, Error};
+    use stylus_sdk::{
+        alloy_primitives::{Address, B256, U256},
+        block, msg,
+    };
+
+    /// Test that balances only count as votes once delegated.
+    #[motsu::test]
+    fn delegate_activates_voting_power(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(contract._mint(holder, U256::from(100)).is_ok());
+        assert_eq!(contract.get_votes(holder), U256::ZERO);
+
+        contract.delegate(holder);
+        assert_eq!(contract.delegates(holder), holder);
+        assert_eq!(contract.get_votes(holder), U256::from(100));
+
+        // Redelegating moves the whole balance
+        contract.delegate(ALICE);
+        assert_eq!(contract.get_votes(holder), U256::ZERO);
+        assert_eq!(contract.get_votes(ALICE), U256::from(100));
+    }
+
+    /// Test that transfers move votes between the delegates of both sides.
+    #[motsu::test]
+    fn transfers_move_delegated_votes(contract: Erc20Aton) {
+        let holder = msg::sender();
+        assert!(contract._mint(holder, U256::from(100)).is_ok());
+        contract.delegate(ALICE);
+        contract.delegates.setter(BOB).set(BOB);
+
+        assert!(contract.transfer(BOB, U256::from(30)).is_ok());
+        assert_eq!(contract.get_votes(ALICE), U256::from(70));
+        assert_eq!(contract.get_votes(BOB), U256::from(30));
+
+        // Burnt tokens leave the delegate's voting power
+        assert!(contract._update(BOB, Address::ZERO, U256::from(10)).is_ok());
+        assert_eq!(contract.get_votes(BOB), U256::from(20));
+    }
+
+    /// Test that past votes and supply are read from block checkpoints.
+    #[motsu::test]
+    fn past_votes_and_total_supply(contract: Erc20Aton) {
+        let holder = msg::sender();
+        shims::set_block_number(10);
+        contract.delegate(holder);
+        assert!(contract._mint(holder, U256::from(100)).is_ok());
+
+        shims::set_block_number(20);
+        assert!(contract._mint(holder, U256::from(50)).is_ok());
+        // A second change in the same block overwrites its checkpoint
+        assert!(contract.transfer(ALICE, U256::from(25)).is_ok());
+
+        shims::set_block_number(30);
+        assert_eq!(contract.get_past_votes(holder, U256::from(9)).ok(), Some(U256::ZERO));
+        assert_eq!(contract.get_past_votes(holder, U256::from(10)).ok(), Some(U256::from(100)));
+        assert_eq!(contract.get_past_votes(holder, U256::from(19)).ok(), Some(U256::from(100)));
+        assert_eq!(contract.get_past_votes(holder, U256::from(20)).ok(), Some(U256::from(125)));
+        assert_eq!(contract.vote_checkpoints.get(holder).len(), 2);
+
+        assert_eq!(contract.get_past_total_supply(U256::from(9)).ok(), Some(U256::ZERO));
+        assert_eq!(contract.get_past_total_supply(U256::from(15)).ok(), Some(U256::from(100)));
+        assert_eq!(contract.get_past_total_supply(U256::from(29)).ok(), Some(U256::from(150)));
+
+        let result = contract.get_past_votes(holder, U256::from(30));
+        assert!(matches!(result, Err(Error::FutureLookup(_))));
+        let result = contract.get_past_total_supply(U256::from(31));
+        assert!(matches!(result, Err(Error::FutureLookup(_))));
+    }
+
+    /// Test that a signed delegation moves the signer's votes and consumes
+    /// its nonce.
+    #[motsu::test]
+    fn delegate_by_sig_delegates_signer(contract: Erc20Aton) {
+        let signer = helpers::signer();
+        let expiry = U256::from(block::timestamp() + 3_600);
+        assert!(contract._mint(signer, U256::from(100)).is_ok());
+
+        let (v, r, s) = helpers::sign(contract._delegation_digest(ALICE, U256::ZERO, expiry));
+        assert!(contract.delegate_by_sig(ALICE, U256::ZERO, expiry, v, r, s).is_ok());
+        assert_eq!(contract.delegates(signer), ALICE);
+        assert_eq!(contract.get_votes(ALICE), U256::from(100));
+        assert_eq!(contract.nonces(signer), U256::from(1));
+
+        // The signature cannot be replayed once the nonce has advanced
+        let result = contract.delegate_by_sig(ALICE, U256::ZERO, expiry, v, r, s);
+        assert!(matches!(result, Err(Error::InvalidAccountNonce(_))));
+    }
+
+    /// Test that delegation signatures are checked for expiry and signer.
+    #[motsu::test]
+    fn delegate_by_sig_rejects_invalid_signatures(contract: Erc20Aton) {
+        let expired = U256::from(block::timestamp() - 1);
+        let result = contract.delegate_by_sig(
+            ALICE,
+            U256::ZERO,
+            expired,
+            27,
+            B256::repeat_byte(1),
+            B256::repeat_byte(1),
+        );
+        assert!(matches!(result, Err(Error::VotesExpiredSignature(_))));
+
+        // `ecrecover` recovers no signer for a `v` other than 27 or 28
+        let expiry = U256::from(block::timestamp() + 3_600);
+        let result = contract.delegate_by_sig(
+            ALICE,
+            U256::ZERO,
+            expiry,
+            29,
+            B256::repeat_byte(1),
+            B256::repeat_byte(1),
+        );
+        assert!(matches!(result, Err(Error::InvalidSignature(_))));
+
+        let result = contract.delegate_by_sig(
+            ALICE,
+            U256::ZERO,
+            expiry,
+            27,
+            B256::repeat_byte(1),
+            B256::repeat_byte(0xff),
+        );
+        assert!(matches!(result, Err(Error::InvalidSignatureS(_))));
+    }
+
+    /// Test the ERC-6372 clock.
+    #[motsu::test]
+    fn clock(contract: Erc20Aton) {
+        shims::set_block_number(42);
+        assert_eq!(contract.clock(), 42);
+        assert_eq!(contract.clock_mode(), "mode=blocknumber&from=default");
+    }
+}
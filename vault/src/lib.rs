@@ -0,0 +1,192 @@
+// Allow `cargo stylus export-abi` to generate a main function.
+#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
+
+extern crate alloc;
+mod test;
+
+// Imports from the Stylus SDK
+use stylus_sdk::prelude::*;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    alloy_sol_types::sol,
+    evm, msg,
+};
+
+// Storage definition for the commission vault.
+sol_storage! {
+    /// Vault storage structure holding the commissions owed to players.
+    ///
+    /// The vault itself holds the ATON that backs the commissions. When a
+    /// player transfers ATON, the token pays the player's commission out of
+    /// the vault's balance and then calls `clearCommission`.
+    ///
+    /// # Fields
+    /// - `owner`: The owner of the vault, allowed to credit commissions.
+    /// - `aton`: Address of the ATON token, the only caller of `clearCommission`.
+    /// - `commissions`: Maps each player to the commission they are owed.
+    /// - `total_commission`: Sum of all outstanding player commissions.
+    /// - `initialized`: Whether `initialize` has run.
+    #[entrypoint]
+    pub struct Vault {
+        /// The owner of the vault with privileged access.
+        address owner;
+        /// Address of the ATON token contract.
+        address aton;
+        /// Mapping of players to their outstanding commission.
+        mapping(address => uint256) commissions;
+        /// Sum of all outstanding player commissions.
+        uint256 total_commission;
+        /// Whether the vault has been initialized.
+        bool initialized;
+    }
+}
+
+// Definition of events and errors for the vault.
+sol! {
+    // Commission events.
+    event CommissionAdded(address indexed player, uint256 amount, uint256 totalCommission);
+    event CommissionCleared(address indexed player, uint256 amount);
+
+    // Access control errors.
+    error UnauthorizedAccount(address account);
+    error Zero(address account);
+    error AlreadyInitialized(address aton);
+}
+
+/// Enum representing the various errors that can occur in the vault.
+#[derive(SolidityError)]
+pub enum Error {
+    /// Thrown when an operation involves an address or value that is zero.
+    Zero(Zero),
+    /// Thrown when a caller without authorization attempts a restricted function.
+    UnauthorizedAccount(UnauthorizedAccount),
+    /// Thrown when the vault is initialized a second time.
+    AlreadyInitialized(AlreadyInitialized),
+}
+
+#[public]
+impl Vault {
+    /// Initializes the vault for the `aton` token and designates the caller
+    /// as the owner. Can only run once.
+    ///
+    /// Stylus contracts have no constructor, so the deployer should call this
+    /// right after deployment and check [`Self::owner`] and [`Self::aton`].
+    /// If another account got there first, this call fails and the vault
+    /// must be redeployed.
+    ///
+    /// # Errors
+    ///
+    /// * If `aton` is `Address::ZERO`, then the error [`Error::Zero`] is
+    ///   returned.
+    /// * If the vault is already initialized, then the error
+    ///   [`Error::AlreadyInitialized`] is returned.
+    pub fn initialize(&mut self, aton: Address) -> Result<(), Error> {
+        if aton.is_zero() {
+            return Err(Error::Zero(Zero { account: aton }));
+        }
+        if self.initialized.get() {
+            return Err(Error::AlreadyInitialized(AlreadyInitialized {
+                aton: self.aton.get(),
+            }));
+        }
+
+        self.initialized.set(true);
+        self.owner.set(msg::sender());
+        self.aton.set(aton);
+        Ok(())
+    }
+
+    /// Returns the owner of the vault.
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    /// Returns the ATON token served by the vault.
+    pub fn aton(&self) -> Address {
+        self.aton.get()
+    }
+
+    /// Returns the commission owed to `player`.
+    pub fn player_commission(&self, player: Address) -> U256 {
+        self.commissions.get(player)
+    }
+
+    /// Returns the sum of all outstanding player commissions.
+    pub fn total_commission(&self) -> U256 {
+        self.total_commission.get()
+    }
+
+    /// Credits `amount` of commission to `player`. Only callable by the owner.
+    ///
+    /// # Errors
+    ///
+    /// * If the caller is not the owner, then the error
+    ///   [`Error::UnauthorizedAccount`] is returned.
+    /// * If `player` is `Address::ZERO`, then the error [`Error::Zero`] is
+    ///   returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`CommissionAdded`] event.
+    pub fn add_commission(&mut self, player: Address, amount: U256) -> Result<(), Error> {
+        self._only_owner()?;
+        if player.is_zero() {
+            return Err(Error::Zero(Zero { account: player }));
+        }
+
+        let commission = self.commissions.get(player) + amount;
+        self.commissions.setter(player).set(commission);
+        self.total_commission.set(self.total_commission.get() + amount);
+
+        evm::log(CommissionAdded {
+            player,
+            amount,
+            totalCommission: commission,
+        });
+
+        Ok(())
+    }
+
+    /// Clears the commission of `player` after the token has paid it out.
+    /// Only callable by the ATON token.
+    ///
+    /// # Errors
+    ///
+    /// If the caller is not the ATON token, then the error
+    /// [`Error::UnauthorizedAccount`] is returned.
+    ///
+    /// # Events
+    ///
+    /// Emits a [`CommissionCleared`] event.
+    pub fn clear_commission(&mut self, player: Address) -> Result<(), Error> {
+        let caller = msg::sender();
+        if caller != self.aton.get() {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
+                account: caller,
+            }));
+        }
+
+        let amount = self.commissions.get(player);
+        self.commissions.setter(player).set(U256::ZERO);
+        self.total_commission.set(self.total_commission.get() - amount);
+
+        evm::log(CommissionCleared { player, amount });
+
+        Ok(())
+    }
+}
+
+// Private Functions
+impl Vault {
+    /// Ensures the caller is the owner of the vault.
+    fn _only_owner(&self) -> Result<(), Error> {
+        let account = msg::sender();
+        if self.owner.get() != account {
+            return Err(Error::UnauthorizedAccount(UnauthorizedAccount {
+                account,
+            }));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+// tests/vault_test.rs
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, Vault};
+    use stylus_sdk::{
+        alloy_primitives::{address, Address, U256},
+        msg,
+    };
+
+    // ATON token address constant used for testing
+    const ATON_ADDRESS: Address = address!("dCE82b5f92C98F27F116F70491a487EFFDb6a2a9");
+    // Player address constant used for testing
+    const PLAYER: Address = address!("00000000000000000000000000000000000a11ce");
+
+    /// Test that the vault can only be initialized once.
+    #[motsu::test]
+    fn initialize(contract: Vault) {
+        let result = contract.initialize(Address::ZERO);
+        assert!(matches!(result, Err(Error::Zero(_))));
+
+        assert!(contract.initialize(ATON_ADDRESS).is_ok());
+        assert_eq!(contract.owner(), msg::sender());
+        assert_eq!(contract.aton(), ATON_ADDRESS);
+
+        let result = contract.initialize(ATON_ADDRESS);
+        assert!(matches!(result, Err(Error::AlreadyInitialized(_))));
+
+        // The flag, not the owner, locks the vault
+        contract.owner.set(Address::ZERO);
+        let result = contract.initialize(PLAYER);
+        assert!(matches!(result, Err(Error::AlreadyInitialized(_))));
+        assert_eq!(contract.aton(), ATON_ADDRESS);
+    }
+
+    /// Test that the owner can credit commissions to players.
+    #[motsu::test]
+    fn add_commission(contract: Vault) {
+        let result = contract.add_commission(PLAYER, U256::from(10));
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+
+        assert!(contract.initialize(ATON_ADDRESS).is_ok());
+        assert!(contract.add_commission(PLAYER, U256::from(10)).is_ok());
+        assert!(contract.add_commission(PLAYER, U256::from(5)).is_ok());
+
+        assert_eq!(contract.player_commission(PLAYER), U256::from(15));
+        assert_eq!(contract.total_commission(), U256::from(15));
+    }
+
+    /// Test that only the ATON token can clear commissions.
+    #[motsu::test]
+    fn clear_commission_only_aton(contract: Vault) {
+        assert!(contract.initialize(ATON_ADDRESS).is_ok());
+        assert!(contract.add_commission(PLAYER, U256::from(10)).is_ok());
+
+        let result = contract.clear_commission(PLAYER);
+        assert!(matches!(result, Err(Error::UnauthorizedAccount(_))));
+        assert_eq!(contract.player_commission(PLAYER), U256::from(10));
+    }
+
+    /// Test that clearing resets the player's commission and the total.
+    #[motsu::test]
+    fn clear_commission(contract: Vault) {
+        // Act as the token so that `clearCommission` is authorized
+        assert!(contract.initialize(msg::sender()).is_ok());
+        assert!(contract.add_commission(PLAYER, U256::from(10)).is_ok());
+
+        assert!(contract.clear_commission(PLAYER).is_ok());
+        assert_eq!(contract.player_commission(PLAYER), U256::ZERO);
+        assert_eq!(contract.total_commission(), U256::ZERO);
+    }
+}